@@ -41,7 +41,7 @@ impl<C: HttpClient> ApiDebugUseCase for ApiDebugUseCaseImpl<C> {
         let formatted_body = response
             .body
             .as_ref()
-            .map(|b| format_response_body(b.content(), b.content_type()));
+            .map(|b| format_response_body(&b.content(), b.content_type()));
 
         Ok(ApiDebugResult {
             response,
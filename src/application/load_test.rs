@@ -1,116 +1,1208 @@
-use crate::domain::entities::{HttpRequest, LoadTestConfig, LoadTestResult, LoadTestState};
-use crate::domain::services::{HttpClient, HttpClientError};
-use crate::domain::value_objects::{Headers, Url};
+use crate::domain::entities::{
+    CheckResult, Feeder, HttpRequest, HttpResponse, LoadProfile, LoadTestConfig, LoadTestProgress,
+    LoadTestResult, LoadTestState, RemainingLoad, RequestMix, RequestRecord, RequestResult,
+    RequestTemplate, RequestTiming, StageResult, TimeSeries,
+};
+use crate::domain::entities::{check_variables, extract_variables, run_checks};
+use crate::domain::services::{HttpClient, HttpClientError, RequestLog};
+use crate::domain::value_objects::{Headers, Url, Variables};
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::Instant;
-use tokio::sync::mpsc;
+use tokio::sync::{Notify, Semaphore, mpsc};
+use tokio::task::JoinSet;
 use tokio::time::Duration;
 
+/// 进度回调的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 闭环模式下按负载曲线调整活跃用户数的间隔
+const CONTROL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 闭环模式下每个虚拟用户的发送间隔
+const USER_TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 无法构建请求（模板渲染失败）时的错误类别
+const TEMPLATE_ERROR: &str = "template";
+
+/// 单个请求的测量结果
+#[derive(Debug, Clone)]
+struct RequestSample {
+    /// 请求在 `Workload::requests` 中的下标
+    request: usize,
+    /// 状态码，请求失败时为 0
+    status: u16,
+    /// 是否实际发出；无法构建的请求没有发出，不计入延迟统计
+    sent: bool,
+    /// 从实际发送到收到响应的耗时
+    latency: Duration,
+    /// 从计划发送时间到收到响应的耗时
+    corrected_latency: Duration,
+    /// 是否为迭代的第一个请求，只有它的计划发送时间来自负载曲线
+    starts_iteration: bool,
+    /// 计划发送时间相对测试开始的偏移
+    scheduled_at: Duration,
+    /// 各阶段耗时，请求失败时为 `None`
+    timing: Option<RequestTiming>,
+    /// 收到了响应但未能提取出后续请求需要的变量
+    extraction_failed: bool,
+    /// 收到了响应但状态码不在请求的期望范围内
+    unexpected_status: bool,
+    /// 请求各检查是否通过，请求失败时为空
+    checks: Vec<bool>,
+    /// 实际发送时间
+    started_at: chrono::DateTime<chrono::Utc>,
+    /// 响应体字节数
+    bytes: u64,
+    /// 请求体字节数
+    bytes_sent: u64,
+    /// 请求失败时的错误类别
+    error: Option<&'static str>,
+    /// 请求失败时的错误消息
+    error_message: Option<String>,
+}
+
+impl RequestSample {
+    /// 没有发出的请求
+    fn failed(start_time: Instant, intended: Instant, kind: &'static str, message: String) -> Self {
+        Self {
+            request: 0,
+            status: 0,
+            sent: false,
+            latency: Duration::ZERO,
+            corrected_latency: intended.elapsed(),
+            starts_iteration: false,
+            scheduled_at: intended.saturating_duration_since(start_time),
+            timing: None,
+            extraction_failed: false,
+            unexpected_status: false,
+            checks: Vec::new(),
+            started_at: chrono::Utc::now(),
+            bytes: 0,
+            bytes_sent: 0,
+            error: Some(kind),
+            error_message: Some(message),
+        }
+    }
+
+    fn checks_passed(&self) -> bool {
+        self.checks.iter().all(|&passed| passed)
+    }
+
+    /// 失败请求的错误类别：请求错误的类别、`status`（状态码不在期望范围内）或
+    /// `check`（检查未通过），与 `LoadTestResult::failed_requests` 的口径相同
+    fn error_kind(&self) -> Option<&'static str> {
+        match self.error {
+            Some(kind) => Some(kind),
+            None if self.unexpected_status => Some("status"),
+            None if !self.checks_passed() => Some("check"),
+            None => None,
+        }
+    }
+}
+
+/// 运行中压测的控制句柄，可以在其他线程上暂停、继续或停止压测，也用作取消令牌
+///
+/// 暂停期间不开始新的迭代，在途请求照常完成；压测时钟不停，暂停的时间计入总时长。
+/// 停止后在宽限期（`LoadTestConfig::grace_period_secs`）内等待在途请求完成，
+/// 返回已收集的结果并标记为已中断。
+#[derive(Debug, Default)]
+pub struct LoadTestControl {
+    paused: AtomicBool,
+    stopped: AtomicBool,
+    stop_notify: Notify,
+    active_users: AtomicUsize,
+}
+
+impl LoadTestControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// 清除暂停和停止状态，每次压测开始时调用，以便同一个句柄用于多次压测
+    pub fn reset(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+        self.stopped.store(false, Ordering::Relaxed);
+        self.active_users.store(0, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.stop_notify.notify_waiters();
+    }
+
+    /// 等待 `stop` 被调用，已停止时立即返回
+    pub async fn stopped(&self) {
+        let notified = self.stop_notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if self.is_stopped() {
+            return;
+        }
+        notified.await;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// 闭环模式下为正在发送的虚拟用户数，开环模式下为在途迭代数
+    pub fn active_users(&self) -> usize {
+        self.active_users.load(Ordering::Relaxed)
+    }
+}
+
+/// 每次迭代的内容：先从数据源取行，再依次发送请求，或按权重抽取一个请求发送
+struct Workload {
+    requests: Vec<RequestTemplate>,
+    mix: Option<RequestMix>,
+    feeders: Vec<Feeder>,
+    /// 置位后不再开始新的迭代
+    stopped: AtomicBool,
+    /// 有数据源已取完，此后的迭代都取不到数据
+    exhausted: AtomicBool,
+    control: Arc<LoadTestControl>,
+    /// 已发出、尚未返回的请求数
+    in_flight: AtomicUsize,
+}
+
+impl Workload {
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed) || self.control.is_stopped()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.control.is_paused()
+    }
+}
+
 #[async_trait]
 pub trait LoadTestUseCase: Send + Sync {
     async fn execute(
         &self,
         config: LoadTestConfig,
-        progress_callback: impl Fn(LoadTestState, LoadTestResult) + Send + 'static,
+        progress_callback: impl Fn(LoadTestState, LoadTestProgress) + Send + 'static,
     ) -> Result<LoadTestResult, HttpClientError>;
 }
 
+/// 负载测试用例
+///
+/// 所有虚拟用户共享同一个注入的客户端及其连接池；
+/// 需要测量建连开销时，注入一个不复用连接的客户端即可。
 pub struct LoadTestUseCaseImpl<C: HttpClient> {
-    client: C,
+    client: Arc<C>,
+    request_logs: Vec<Arc<dyn RequestLog>>,
+    control: Arc<LoadTestControl>,
 }
 
 impl<C: HttpClient> LoadTestUseCaseImpl<C> {
     pub fn new(client: C) -> Self {
-        Self { client }
+        Self {
+            client: Arc::new(client),
+            request_logs: Vec::new(),
+            control: Arc::new(LoadTestControl::new()),
+        }
+    }
+
+    /// 把每个请求（包括预热请求）的原始记录交给 `request_log`，可多次调用
+    pub fn with_request_log(mut self, request_log: Arc<dyn RequestLog>) -> Self {
+        self.request_logs.push(request_log);
+        self
+    }
+
+    /// 使用外部创建的控制句柄，以便在压测过程中暂停、继续或停止
+    pub fn with_control(mut self, control: Arc<LoadTestControl>) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub fn control(&self) -> &Arc<LoadTestControl> {
+        &self.control
     }
 }
 
 #[async_trait]
-impl<C: HttpClient> LoadTestUseCase for LoadTestUseCaseImpl<C> {
+impl<C: HttpClient + 'static> LoadTestUseCase for LoadTestUseCaseImpl<C> {
     async fn execute(
         &self,
         config: LoadTestConfig,
-        progress_callback: impl Fn(LoadTestState, LoadTestResult) + Send + 'static,
+        progress_callback: impl Fn(LoadTestState, LoadTestProgress) + Send + 'static,
     ) -> Result<LoadTestResult, HttpClientError> {
-        let mut result = LoadTestResult::default();
+        self.control.reset();
+        let profile = Arc::new(
+            config
+                .load_profile()
+                .map_err(HttpClientError::InvalidConfig)?,
+        );
 
-        let (sender, mut receiver) = mpsc::channel::<(u16, Duration)>(10000);
+        let mut result = LoadTestResult::with_latency_precision(config.latency_precision);
+        result.stage_results = config
+            .stages
+            .iter()
+            .enumerate()
+            .map(|(index, stage)| StageResult {
+                name: stage.display_name(index),
+                target: stage.target,
+                duration_secs: stage.duration_secs,
+                result: LoadTestResult::with_latency_precision(config.latency_precision),
+            })
+            .collect();
 
-        let config = Arc::new(config);
-        let request_url = config.url.clone();
-        let request_method = config.method.clone();
-        let duration_secs = config.duration_secs;
-
-        let worker = tokio::spawn(async move {
+        let requests = if config.requests.is_empty() {
             let request = HttpRequest {
                 id: uuid::Uuid::new_v4(),
-                url: Url::parse(&request_url).unwrap(),
-                method: request_method.parse().unwrap(),
+                url: Url::parse(&config.url)
+                    .map_err(|e| HttpClientError::InvalidUrl(e.to_string()))?,
+                method: config.method.parse().map_err(HttpClientError::InvalidUrl)?,
                 headers: Headers::new(),
                 body: None,
                 timeout: Duration::from_secs(30),
             };
+            let name = format!("{} {}", request.method, request.url);
+            vec![
+                RequestTemplate::new(name, request)
+                    .with_expected_statuses(config.expected_statuses.clone()),
+            ]
+        } else {
+            config.requests.clone()
+        };
+        check_variables(
+            &requests,
+            config
+                .feeders
+                .iter()
+                .flat_map(|feeder| feeder.columns().iter().map(String::as_str)),
+        )
+        .map_err(HttpClientError::InvalidConfig)?;
+        let mix = RequestMix::new(&requests).map_err(HttpClientError::InvalidConfig)?;
 
-            let start_time = Instant::now();
-            let mut handles = Vec::new();
-
-            for _ in 0..config.concurrent_users {
-                let sender = sender.clone();
-                let request = request.clone();
-
-                let handle = tokio::spawn(async move {
-                    let mut interval = tokio::time::interval(Duration::from_millis(100));
-                    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
-                    loop {
-                        interval.tick().await;
-                        if Instant::now().duration_since(start_time)
-                            > Duration::from_secs(duration_secs)
-                        {
-                            break;
-                        }
-
-                        let start = Instant::now();
-                        let response = crate::infrastructure::http::ReqwestClient::new()
-                            .unwrap()
-                            .execute(&request)
-                            .await;
-
-                        match response {
-                            Ok(resp) => {
-                                if sender.send((resp.status, start.elapsed())).await.is_err() {
-                                    break;
-                                }
-                            }
-                            Err(_) => {
-                                if sender.send((0, start.elapsed())).await.is_err() {
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                });
+        if requests.len() > 1 {
+            result.request_results = requests
+                .iter()
+                .enumerate()
+                .map(|(index, template)| RequestResult {
+                    name: template.name.clone(),
+                    share: mix.as_ref().map(|mix| mix.share(index)),
+                    result: LoadTestResult::with_latency_precision(config.latency_precision),
+                })
+                .collect();
+        }
 
-                handles.push(handle);
-            }
+        // 每个请求的检查在 `result.checks` 中的起始位置
+        let mut check_offsets = Vec::with_capacity(requests.len());
+        for template in &requests {
+            check_offsets.push(result.checks.len());
+            result.checks.extend(
+                template
+                    .checks
+                    .iter()
+                    .map(|check| CheckResult::new(template.name.as_str(), check.name.as_str())),
+            );
+        }
 
-            for handle in handles {
-                let _ = handle.await;
-            }
+        let workload = Arc::new(Workload {
+            requests,
+            mix,
+            // 克隆的数据源共享取行进度，重新开始以便同一份配置多次压测
+            feeders: config.feeders.iter().map(Feeder::restart).collect(),
+            stopped: AtomicBool::new(false),
+            exhausted: AtomicBool::new(false),
+            control: self.control.clone(),
+            in_flight: AtomicUsize::new(0),
         });
 
-        while let Some((status, duration)) = receiver.recv().await {
-            result.add_response(status, duration);
-            result.calculate_qps();
-            progress_callback(LoadTestState::Running, result.clone());
+        let (sender, mut receiver) = mpsc::channel::<RequestSample>(10000);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        // 只有闭环模式存在固定的期望发送间隔，开环模式的计划时间本身已避免遗漏
+        let backfill_interval = (!profile.is_rate() && config.coordinated_omission_backfill)
+            .then_some(USER_TICK_INTERVAL);
+
+        let start_time = Instant::now();
+        let worker = if profile.is_rate() {
+            tokio::spawn(run_open_model(
+                self.client.clone(),
+                profile.clone(),
+                config.max_in_flight,
+                start_time,
+                workload.clone(),
+                sender,
+                dropped.clone(),
+            ))
+        } else {
+            tokio::spawn(run_closed_model(
+                self.client.clone(),
+                profile.clone(),
+                start_time,
+                workload.clone(),
+                sender,
+            ))
+        };
+
+        progress_callback(profile.state_at(Duration::ZERO), result.progress());
+
+        // 结果包含完整的延迟直方图，按固定间隔推送进度快照而不是每个响应都克隆
+        // 时间序列随压测增长，不放进每次克隆的进度快照，结束时再填入结果
+        let mut time_series = TimeSeries::new();
+        let warmup = profile.warmup();
+        // 停止后等待在途请求的截止时间
+        let mut drain_deadline: Option<tokio::time::Instant> = None;
+        let grace_period = config.grace_period();
+        // 宽限期已过、放弃了剩余的在途请求
+        let mut abandoned = false;
+
+        let mut progress = ProgressReporter {
+            profile: &profile,
+            config: &config,
+            workload: &workload,
+            dropped: &dropped,
+            start_time,
+            last_report: Instant::now(),
+        };
+        // 把一个样本计入请求日志、结果和时间序列
+        let record =
+            |result: &mut LoadTestResult, time_series: &mut TimeSeries, sample: &RequestSample| {
+                if let Some((last, others)) = self.request_logs.split_last() {
+                    let record = request_record(&workload, sample, warmup);
+                    for request_log in others {
+                        request_log.record(record.clone());
+                    }
+                    last.record(record);
+                }
+                if sample.scheduled_at < warmup {
+                    result.warmup_requests += 1;
+                } else {
+                    record_sample(result, sample, backfill_interval);
+                    time_series.record(
+                        sample.scheduled_at + sample.corrected_latency,
+                        sample.sent.then_some(sample.latency),
+                        sample.bytes,
+                        sample.error_kind(),
+                    );
+                    if !result.stage_results.is_empty() {
+                        let stage = profile.stage_index_at(sample.scheduled_at);
+                        record_sample(
+                            &mut result.stage_results[stage].result,
+                            sample,
+                            backfill_interval,
+                        );
+                    }
+                    if let Some(request) = result.request_results.get_mut(sample.request) {
+                        record_sample(&mut request.result, sample, backfill_interval);
+                    }
+                    let offset = check_offsets[sample.request];
+                    for (check, &passed) in result.checks[offset..].iter_mut().zip(&sample.checks) {
+                        check.record(passed);
+                    }
+                }
+            };
+        let mut progress_tick = tokio::time::interval(PROGRESS_INTERVAL);
+        progress_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            let drain_timeout = async move {
+                match drain_deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let sample = tokio::select! {
+                sample = receiver.recv() => match sample {
+                    Some(sample) => sample,
+                    None => break,
+                },
+                _ = progress_tick.tick() => {
+                    progress.report(&mut result, &mut drain_deadline, &progress_callback);
+                    continue;
+                }
+                _ = self.control.stopped(), if drain_deadline.is_none() => {
+                    drain_deadline = Some(tokio::time::Instant::now() + grace_period);
+                    continue;
+                }
+                _ = drain_timeout => {
+                    // 宽限期已过，不再等待剩余的在途请求
+                    worker.abort();
+                    abandoned = true;
+                    break;
+                }
+            };
+            record(&mut result, &mut time_series, &sample);
+            progress.report(&mut result, &mut drain_deadline, &progress_callback);
         }
 
         let _ = worker.await;
+        if abandoned {
+            // 已经完成、还在通道中的样本照常计入，之后仍未返回的才算放弃
+            while let Ok(sample) = receiver.try_recv() {
+                record(&mut result, &mut time_series, &sample);
+            }
+            result.abandoned_requests = workload.in_flight.load(Ordering::Relaxed) as u64;
+        }
+        self.control.active_users.store(0, Ordering::Relaxed);
+        for request_log in &self.request_logs {
+            request_log.finish().map_err(HttpClientError::IoError)?;
+        }
 
-        result.calculate_qps();
-        progress_callback(LoadTestState::Completed, result.clone());
+        result.dropped_requests = dropped.load(Ordering::Relaxed);
+        result.time_series = time_series;
+        result.interrupted = self.control.is_stopped();
+        result.data_exhausted = workload.exhausted.load(Ordering::Relaxed);
+        refresh_statistics(&mut result, &profile, start_time.elapsed());
+        let state = if result.interrupted {
+            LoadTestState::Interrupted
+        } else {
+            LoadTestState::Completed
+        };
+        progress_callback(state, result.progress());
 
         Ok(result)
     }
 }
+
+/// 压测过程中的进度推送
+///
+/// 刷新统计、检查可提前中止的阈值并调用进度回调。每收到一个样本和每个进度定时器
+/// 周期都会调用，被测服务卡住、没有样本返回时仪表盘和阈值检查也照常更新。
+struct ProgressReporter<'a> {
+    profile: &'a LoadProfile,
+    config: &'a LoadTestConfig,
+    workload: &'a Workload,
+    dropped: &'a AtomicU64,
+    start_time: Instant,
+    last_report: Instant,
+}
+
+impl ProgressReporter<'_> {
+    /// 距上次推送不足 `PROGRESS_INTERVAL` 时不做任何事；阈值已无法满足时停止压测并设置宽限期
+    fn report(
+        &mut self,
+        result: &mut LoadTestResult,
+        drain_deadline: &mut Option<tokio::time::Instant>,
+        progress_callback: &impl Fn(LoadTestState, LoadTestProgress),
+    ) {
+        if self.last_report.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_report = Instant::now();
+        let elapsed = self.start_time.elapsed();
+        result.dropped_requests = self.dropped.load(Ordering::Relaxed);
+        refresh_statistics(result, self.profile, elapsed);
+        let remaining = remaining_load(self.profile, self.config, self.workload, elapsed);
+        if result.aborted.is_none()
+            && let Some(threshold) = self
+                .config
+                .thresholds
+                .iter()
+                .find(|t| t.abort_on_fail && t.is_unrecoverable(result, &remaining))
+        {
+            result.aborted = Some(format!("阈值 {} 已无法满足", threshold));
+            self.workload.stopped.store(true, Ordering::Relaxed);
+            *drain_deadline = Some(tokio::time::Instant::now() + self.config.grace_period());
+        }
+        let state = match self.profile.state_at(elapsed) {
+            // 时间到了但仍有在途请求未返回
+            LoadTestState::Completed => LoadTestState::Running,
+            _ if self.workload.is_paused() => LoadTestState::Paused,
+            state => state,
+        };
+        progress_callback(state, result.progress());
+    }
+}
+
+fn record_sample(
+    result: &mut LoadTestResult,
+    sample: &RequestSample,
+    backfill_interval: Option<Duration>,
+) {
+    if !sample.sent {
+        if let (Some(kind), Some(message)) = (sample.error, &sample.error_message) {
+            result.add_unsent_failure(kind, message);
+        }
+        return;
+    }
+    result.add_response(sample.status, sample.latency, sample.error_kind().is_none());
+    if let (Some(kind), Some(message)) = (sample.error, &sample.error_message) {
+        result.add_error(kind, message);
+    }
+    // 补记已经覆盖了被阻塞的等待，此时按实际延迟补记，避免与计划时间重复校正
+    match backfill_interval {
+        Some(interval) if sample.starts_iteration => {
+            result.add_corrected_latency(sample.latency, Some(interval))
+        }
+        _ => result.add_corrected_latency(sample.corrected_latency, None),
+    }
+    result.bytes_sent += sample.bytes_sent;
+    result.bytes_received += sample.bytes;
+    if let Some(timing) = &sample.timing {
+        result.phase_timings.record(timing);
+    }
+    if sample.unexpected_status {
+        result.unexpected_statuses += 1;
+    }
+    if sample.extraction_failed {
+        result.extraction_failures += 1;
+    }
+}
+
+fn request_record(workload: &Workload, sample: &RequestSample, warmup: Duration) -> RequestRecord {
+    RequestRecord {
+        timestamp: sample.started_at,
+        request: workload.requests[sample.request].name.clone(),
+        status: sample.status,
+        latency: sample.latency,
+        bytes: sample.bytes,
+        bytes_sent: sample.bytes_sent,
+        error: sample.error_kind().map(str::to_string),
+        warmup: sample.scheduled_at < warmup,
+    }
+}
+
+/// 压测剩余部分的上界
+///
+/// 开环模式下剩余请求数不超过此后计划到达的迭代数加上可能仍在途的迭代数，
+/// 乘以每次迭代的请求数；闭环模式的请求数取决于响应速度，无法预知。
+fn remaining_load(
+    profile: &LoadProfile,
+    config: &LoadTestConfig,
+    workload: &Workload,
+    elapsed: Duration,
+) -> RemainingLoad {
+    let total = profile.total_duration();
+    let max_requests = profile.is_rate().then(|| {
+        let arrivals =
+            profile.arrivals_until(total) - profile.arrivals_until(elapsed.max(profile.warmup()));
+        let iterations = arrivals.max(0.0).ceil() as u64 + 1 + config.max_in_flight as u64;
+        let per_iteration = if workload.mix.is_some() {
+            1
+        } else {
+            workload.requests.len() as u64
+        };
+        iterations * per_iteration
+    });
+    RemainingLoad {
+        max_requests,
+        planned_elapsed: total.saturating_sub(profile.warmup()),
+    }
+}
+
+/// 刷新统计，`elapsed` 为测试开始至今的墙钟时间
+///
+/// 吞吐按扣除预热后的墙钟时间计算；各阶段只计入阶段时间窗口内已经过去的部分，
+/// 各请求与整体共用同一段时间。
+fn refresh_statistics(result: &mut LoadTestResult, profile: &LoadProfile, elapsed: Duration) {
+    let warmup = profile.warmup();
+    result.elapsed = elapsed.saturating_sub(warmup);
+    result.calculate_qps();
+    result.calculate_percentiles();
+    for (index, stage) in result.stage_results.iter_mut().enumerate() {
+        let (start, end) = profile.stage_window(index);
+        stage.result.elapsed = end.min(elapsed).saturating_sub(start.max(warmup));
+        stage.result.calculate_qps();
+        stage.result.calculate_percentiles();
+    }
+    for request in &mut result.request_results {
+        request.result.elapsed = result.elapsed;
+        request.result.calculate_qps();
+        request.result.calculate_percentiles();
+    }
+}
+
+/// 闭环模型：每个虚拟用户等待上一次迭代完成后再开始下一次迭代
+///
+/// 虚拟用户的变量在迭代之间保留。控制循环按负载曲线调整活跃用户数：用户按序号懒启动，
+/// 序号超出当前目标的用户暂停发送，目标回升后继续。
+async fn run_closed_model<C: HttpClient + 'static>(
+    client: Arc<C>,
+    profile: Arc<LoadProfile>,
+    start_time: Instant,
+    workload: Arc<Workload>,
+    sender: mpsc::Sender<RequestSample>,
+) {
+    let test_duration = profile.total_duration();
+    let end_time = start_time + test_duration;
+    let active_users = Arc::new(AtomicUsize::new(0));
+    // 任务随 `JoinSet` 一起释放：驱动任务被中止时，虚拟用户及其在途请求也一并中止
+    let mut users = JoinSet::new();
+    let mut spawned = 0;
+
+    while start_time.elapsed() < test_duration && !workload.is_stopped() {
+        let target = profile.target_at(start_time.elapsed()).ceil() as usize;
+        active_users.store(target, Ordering::Relaxed);
+        let active = if workload.is_paused() { 0 } else { target };
+        workload
+            .control
+            .active_users
+            .store(active, Ordering::Relaxed);
+
+        while spawned < target {
+            let user = spawned;
+            spawned += 1;
+            let client = client.clone();
+            let sender = sender.clone();
+            let workload = workload.clone();
+            let active_users = active_users.clone();
+
+            users.spawn(async move {
+                let mut interval = tokio::time::interval(USER_TICK_INTERVAL);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                let mut paused = false;
+                let mut variables = Variables::new();
+
+                loop {
+                    if start_time.elapsed() > test_duration || workload.is_stopped() {
+                        break;
+                    }
+                    if user >= active_users.load(Ordering::Relaxed) || workload.is_paused() {
+                        paused = true;
+                        tokio::time::sleep(CONTROL_INTERVAL).await;
+                        continue;
+                    }
+                    if paused {
+                        // 暂停期间错过的 tick 不是被遗漏的请求
+                        interval.reset_immediately();
+                        paused = false;
+                    }
+
+                    // 迟到的 tick 返回的是原定的计划时间
+                    let intended = interval.tick().await.into_std();
+                    if start_time.elapsed() > test_duration
+                        || workload.is_stopped()
+                        || !feed_variables(&workload, user, &mut variables)
+                    {
+                        break;
+                    }
+
+                    let running = run_iteration(
+                        &*client,
+                        &workload,
+                        start_time,
+                        intended,
+                        &mut variables,
+                        &sender,
+                    )
+                    .await;
+                    if !running {
+                        break;
+                    }
+                }
+            });
+        }
+
+        let next_check = (Instant::now() + CONTROL_INTERVAL).min(end_time);
+        tokio::time::sleep_until(next_check.into()).await;
+    }
+
+    while users.join_next().await.is_some() {}
+}
+
+/// 开环模型：按负载曲线给出的到达时间线开始迭代，与响应快慢无关
+///
+/// 每次迭代视为一个新的虚拟用户，从空的变量表开始。在途迭代达到
+/// `max_in_flight` 时，本应开始的迭代计入 `dropped`，表示施压端没能维持目标速率。
+async fn run_open_model<C: HttpClient + 'static>(
+    client: Arc<C>,
+    profile: Arc<LoadProfile>,
+    max_in_flight: usize,
+    start_time: Instant,
+    workload: Arc<Workload>,
+    sender: mpsc::Sender<RequestSample>,
+    dropped: Arc<AtomicU64>,
+) {
+    let test_duration = profile.total_duration();
+    let end_time = start_time + test_duration;
+    let max_in_flight = max_in_flight.max(1);
+    let in_flight = Arc::new(Semaphore::new(max_in_flight));
+    // 任务随 `JoinSet` 一起释放：驱动任务被中止时，在途迭代也一并中止
+    let mut iterations = JoinSet::new();
+    let mut scheduled = 0u64;
+
+    'schedule: loop {
+        let elapsed = start_time.elapsed();
+        if elapsed >= test_duration || workload.is_stopped() {
+            break;
+        }
+
+        workload.control.active_users.store(
+            max_in_flight - in_flight.available_permits(),
+            Ordering::Relaxed,
+        );
+
+        // 按时间线补齐所有已到期的请求，定时器精度不足时也不会少发
+        let due = profile.arrivals_until(elapsed) as u64 + 1;
+        if workload.is_paused() {
+            // 暂停期间到期的请求直接跳过，不计入 `dropped`
+            scheduled = scheduled.max(due);
+        }
+        while scheduled < due {
+            let Some(offset) = profile.arrival_time(scheduled) else {
+                break 'schedule;
+            };
+            let intended = start_time + offset;
+            let user = scheduled as usize;
+            scheduled += 1;
+            let permit = match in_flight.clone().try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+            };
+
+            let client = client.clone();
+            let sender = sender.clone();
+            let workload = workload.clone();
+            iterations.spawn(async move {
+                let mut variables = Variables::new();
+                if !feed_variables(&workload, user, &mut variables) {
+                    return;
+                }
+                run_iteration(
+                    &*client,
+                    &workload,
+                    start_time,
+                    intended,
+                    &mut variables,
+                    &sender,
+                )
+                .await;
+                drop(permit);
+            });
+        }
+        // 回收已结束的迭代，避免长时间压测中积累
+        while iterations.try_join_next().is_some() {}
+
+        let Some(next_offset) = profile.arrival_time(scheduled) else {
+            break;
+        };
+        let next_due = (start_time + next_offset).min(end_time);
+        tokio::time::sleep_until(next_due.into()).await;
+    }
+
+    // 等待在途请求全部完成
+    while iterations.join_next().await.is_some() {}
+}
+
+/// 迭代开始前从每个数据源取一行写入变量表
+///
+/// 有数据源已取完时返回 `false`，并停止整个压测：此后所有虚拟用户都取不到数据，
+/// 继续等到计划时长结束只会空转。
+fn feed_variables(workload: &Workload, user: usize, variables: &mut Variables) -> bool {
+    for feeder in &workload.feeders {
+        let Some(row) = feeder.next_row(user) else {
+            workload.exhausted.store(true, Ordering::Relaxed);
+            workload.stopped.store(true, Ordering::Relaxed);
+            return false;
+        };
+        variables.extend(row.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    true
+}
+
+/// 发送一次迭代中的请求，结果接收端关闭时返回 `false`
+///
+/// 按权重混合时只发送抽到的一个请求，否则依次发送全部请求：只有第一个请求有计划发送时间，
+/// 后续请求在前一个完成后立即发出。请求的提取规则没能取到值时，后续请求缺少变量，
+/// 本次迭代提前结束；无法构建请求时同样提前结束，并计为一个 `template` 类别的失败请求。
+async fn run_iteration<C: HttpClient>(
+    client: &C,
+    workload: &Workload,
+    start_time: Instant,
+    intended: Instant,
+    variables: &mut Variables,
+    sender: &mpsc::Sender<RequestSample>,
+) -> bool {
+    let indices = match &workload.mix {
+        Some(mix) => {
+            let index = mix.sample();
+            index..index + 1
+        }
+        None => 0..workload.requests.len(),
+    };
+
+    let mut intended = intended;
+    let first = indices.start;
+    for index in indices {
+        let template = &workload.requests[index];
+        let request = match template.request.render(variables) {
+            Ok(request) => request,
+            Err(e) => {
+                // 数据行缺列等原因导致无法构建请求时计为失败请求，本次迭代提前结束
+                let sample = RequestSample {
+                    request: index,
+                    starts_iteration: index == first,
+                    ..RequestSample::failed(start_time, intended, TEMPLATE_ERROR, e)
+                };
+                return sender.send(sample).await.is_ok();
+            }
+        };
+
+        workload.in_flight.fetch_add(1, Ordering::Relaxed);
+        let (mut sample, response) = send_request(client, &request, start_time, intended).await;
+        workload.in_flight.fetch_sub(1, Ordering::Relaxed);
+        sample.request = index;
+        sample.starts_iteration = index == first;
+        if let Some(response) = &response {
+            sample.unexpected_status = !template.expects_status(response.status);
+            sample.checks = run_checks(&template.checks, response, sample.latency);
+        }
+        let extracted = template.extract.is_empty()
+            || response.as_ref().is_some_and(|response| {
+                extract_variables(&template.extract, response, variables).is_ok()
+            });
+        sample.extraction_failed = response.is_some() && !extracted;
+
+        if sender.send(sample).await.is_err() {
+            return false;
+        }
+        if !extracted {
+            break;
+        }
+        intended = Instant::now();
+    }
+    true
+}
+
+/// 发送单个请求，`intended` 为该请求的计划发送时间
+async fn send_request<C: HttpClient>(
+    client: &C,
+    request: &HttpRequest,
+    start_time: Instant,
+    intended: Instant,
+) -> (RequestSample, Option<HttpResponse>) {
+    let started_at = chrono::Utc::now();
+    let start = Instant::now();
+    let (response, error, error_message) = match client.execute(request).await {
+        Ok(response) => (Some(response), None, None),
+        Err(e) => (None, Some(e.kind()), Some(e.to_string())),
+    };
+
+    let sample = RequestSample {
+        request: 0,
+        status: response.as_ref().map_or(0, |resp| resp.status),
+        sent: true,
+        latency: start.elapsed(),
+        corrected_latency: intended.elapsed(),
+        starts_iteration: false,
+        scheduled_at: intended.saturating_duration_since(start_time),
+        timing: response.as_ref().map(|resp| resp.timing),
+        extraction_failed: false,
+        unexpected_status: false,
+        checks: Vec::new(),
+        started_at,
+        bytes: response.as_ref().map_or(0, |resp| resp.body_size),
+        bytes_sent: request.body.as_ref().map_or(0, |body| body.len() as u64),
+        error,
+        error_message,
+    };
+    (sample, response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Check, FeedStrategy, HttpResponse};
+    use crate::domain::value_objects::Body;
+
+    /// 固定延迟后返回 200 的客户端
+    struct StubClient {
+        delay: Duration,
+        /// 返回的 JSON 响应体
+        body: Option<&'static str>,
+        /// 已返回响应的请求数
+        completed: Arc<AtomicU64>,
+    }
+
+    impl StubClient {
+        fn new(delay: Duration) -> Self {
+            Self {
+                delay,
+                body: None,
+                completed: Arc::new(AtomicU64::new(0)),
+            }
+        }
+
+        fn with_body(mut self, body: &'static str) -> Self {
+            self.body = Some(body);
+            self
+        }
+    }
+
+    #[async_trait]
+    impl HttpClient for StubClient {
+        async fn execute(&self, _request: &HttpRequest) -> Result<HttpResponse, HttpClientError> {
+            tokio::time::sleep(self.delay).await;
+            self.completed.fetch_add(1, Ordering::Relaxed);
+            Ok(HttpResponse {
+                status: 200,
+                headers: Headers::new(),
+                body: self.body.map(Body::json),
+                body_size: self.body.map_or(2, |body| body.len() as u64),
+                duration: self.delay,
+                timestamp: chrono::Utc::now(),
+                timing: RequestTiming::default(),
+            })
+        }
+
+        async fn close(&self) -> Result<(), HttpClientError> {
+            Ok(())
+        }
+    }
+
+    fn config() -> LoadTestConfig {
+        LoadTestConfig {
+            url: "http://stub.test/".to_string(),
+            concurrent_users: 1,
+            duration_secs: 1,
+            warmup_secs: 0,
+            ramp_up_secs: 0,
+            ..LoadTestConfig::default()
+        }
+    }
+
+    fn request(url: &str) -> RequestTemplate {
+        let request = HttpRequest {
+            url: Url::parse(url).unwrap(),
+            ..HttpRequest::default()
+        };
+        RequestTemplate::new(url, request)
+    }
+
+    fn feeder(rows: &[&[(&str, &str)]], strategy: FeedStrategy) -> Feeder {
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .collect();
+        Feeder::new("rows", rows, strategy).unwrap()
+    }
+
+    async fn run(delay: Duration, config: LoadTestConfig) -> LoadTestResult {
+        LoadTestUseCaseImpl::new(StubClient::new(delay))
+            .execute(config, |_, _| {})
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unsent_requests_fail_without_touching_latency() {
+        let delay = Duration::from_millis(5);
+        let config = LoadTestConfig {
+            requests: vec![request("http://stub.test/users/{{id}}")],
+            feeders: vec![feeder(
+                &[&[("id", "1")], &[("name", "no id")]],
+                FeedStrategy::Circular,
+            )],
+            // 开环模式每次迭代从空的变量表开始，缺列的行不会沿用上一行的值
+            arrival_rate: Some(20.0),
+            ..config()
+        };
+        let result = run(delay, config).await;
+
+        let template_errors = result.errors[TEMPLATE_ERROR].count;
+        assert!(template_errors > 0);
+        assert_eq!(result.unsent_requests, template_errors);
+        assert_eq!(result.failed_requests, template_errors);
+        assert_eq!(result.successful_requests, result.sent_requests());
+        assert_eq!(result.latency_histogram.len(), result.sent_requests());
+        assert_eq!(result.corrected_histogram.len(), result.sent_requests());
+        assert!(result.min_latency >= delay);
+        assert!(result.avg_latency >= delay);
+        assert!(result.p50_latency >= delay);
+    }
+
+    #[tokio::test]
+    async fn unique_feeder_ends_the_run_and_restarts_per_execute() {
+        let config = LoadTestConfig {
+            requests: vec![request("http://stub.test/users/{{id}}")],
+            feeders: vec![feeder(
+                &[&[("id", "1")], &[("id", "2")], &[("id", "3")]],
+                FeedStrategy::Unique,
+            )],
+            duration_secs: 30,
+            ..config()
+        };
+        let use_case = LoadTestUseCaseImpl::new(StubClient::new(Duration::from_millis(1)));
+        for _ in 0..2 {
+            let started = Instant::now();
+            let result = use_case.execute(config.clone(), |_, _| {}).await.unwrap();
+            assert_eq!(result.total_requests, 3);
+            assert!(result.data_exhausted);
+            assert!(!result.interrupted);
+            assert!(started.elapsed() < Duration::from_secs(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_progress_while_the_target_stalls() {
+        let before_first_response = Arc::new(AtomicUsize::new(0));
+        let counter = before_first_response.clone();
+        let result = LoadTestUseCaseImpl::new(StubClient::new(Duration::from_millis(1500)))
+            .execute(config(), move |state, progress| {
+                if progress.total_requests == 0 && state == LoadTestState::Running {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_requests, 1);
+        assert!(before_first_response.load(Ordering::Relaxed) >= 5);
+    }
+
+    #[tokio::test]
+    async fn keeps_completed_samples_when_the_grace_period_expires() {
+        let client = StubClient::new(Duration::from_millis(200));
+        let completed = client.completed.clone();
+        let use_case = LoadTestUseCaseImpl::new(client);
+        let control = use_case.control().clone();
+        let config = LoadTestConfig {
+            concurrent_users: 20,
+            duration_secs: 10,
+            // 错开用户的启动时间，停止时总有请求在途，而不是恰好落在两轮请求之间
+            ramp_up_secs: 1,
+            grace_period_secs: 0,
+            ..config()
+        };
+        let result = use_case
+            .execute(config, move |_, progress| {
+                if progress.total_requests >= 30 {
+                    control.stop();
+                }
+            })
+            .await
+            .unwrap();
+        assert!(result.interrupted);
+        assert!(result.abandoned_requests > 0);
+        assert_eq!(result.total_requests, completed.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn counts_checks_per_request_and_fails_the_request() {
+        let check = |repr: serde_json::Value| -> Check { serde_json::from_value(repr).unwrap() };
+        let mut healthy = request("http://stub.test/health");
+        healthy.checks = vec![check(serde_json::json!({ "status": 200 }))];
+        let mut orders = request("http://stub.test/orders");
+        orders.checks = vec![
+            check(serde_json::json!({ "status": 200 })),
+            check(serde_json::json!({ "name": "ok", "json": "$.ok", "equals": true })),
+        ];
+        let config = LoadTestConfig {
+            requests: vec![healthy, orders],
+            ..config()
+        };
+        let client = StubClient::new(Duration::from_millis(5)).with_body(r#"{"ok": false}"#);
+        let result = LoadTestUseCaseImpl::new(client)
+            .execute(config, |_, _| {})
+            .await
+            .unwrap();
+
+        let count = |request: &str, name: &str| {
+            let check = result
+                .checks
+                .iter()
+                .find(|check| check.request == request && check.name == name)
+                .unwrap();
+            (check.passed, check.failed)
+        };
+        let iterations = result.total_requests / 2;
+        assert!(iterations > 0);
+        assert_eq!(result.checks.len(), 3);
+        assert_eq!(
+            count("http://stub.test/health", "status in [200]"),
+            (iterations, 0)
+        );
+        assert_eq!(
+            count("http://stub.test/orders", "status in [200]"),
+            (iterations, 0)
+        );
+        assert_eq!(count("http://stub.test/orders", "ok"), (0, iterations));
+        assert_eq!(result.failed_requests, iterations);
+    }
+
+    #[tokio::test]
+    async fn open_model_keeps_the_arrival_rate_of_a_slow_target() {
+        let config = LoadTestConfig {
+            arrival_rate: Some(50.0),
+            ..config()
+        };
+        // 单个闭环用户在 100ms 的延迟下每秒最多发出 10 个请求
+        let result = run(Duration::from_millis(100), config).await;
+        assert!(
+            (45..=52).contains(&result.total_requests),
+            "{}",
+            result.total_requests
+        );
+        assert_eq!(result.dropped_requests, 0);
+        assert_eq!(result.successful_requests, result.total_requests);
+    }
+
+    #[tokio::test]
+    async fn drops_arrivals_beyond_max_in_flight() {
+        let config = LoadTestConfig {
+            arrival_rate: Some(100.0),
+            max_in_flight: 2,
+            ..config()
+        };
+        let result = run(Duration::from_millis(200), config).await;
+        // 两个在途名额每 200ms 各完成一个请求
+        assert!(
+            (8..=12).contains(&result.total_requests),
+            "{}",
+            result.total_requests
+        );
+        assert!(result.dropped_requests >= 80, "{}", result.dropped_requests);
+        assert!(result.total_requests + result.dropped_requests <= 102);
+    }
+
+    #[tokio::test]
+    async fn warmup_samples_stay_out_of_the_result() {
+        let config = LoadTestConfig {
+            warmup_secs: 1,
+            ..config()
+        };
+        let result = run(Duration::from_millis(10), config).await;
+        assert!(result.warmup_requests >= 8, "{}", result.warmup_requests);
+        assert!(result.total_requests >= 8, "{}", result.total_requests);
+        assert_eq!(result.latency_histogram.len(), result.total_requests);
+        assert_eq!(result.corrected_histogram.len(), result.total_requests);
+        assert!(result.elapsed < Duration::from_millis(1500));
+    }
+
+    #[tokio::test]
+    async fn backfills_samples_missed_by_a_slow_closed_model_user() {
+        let delay = Duration::from_millis(350);
+        let without = run(delay, config()).await;
+        assert_eq!(
+            without.corrected_histogram.len(),
+            without.latency_histogram.len()
+        );
+
+        let config = LoadTestConfig {
+            coordinated_omission_backfill: true,
+            ..config()
+        };
+        let with = run(delay, config).await;
+        // 每个 350ms 的响应阻塞了约 3 个 100ms 的发送间隔
+        assert!(
+            with.corrected_histogram.len() >= with.latency_histogram.len() * 3,
+            "{} vs {}",
+            with.corrected_histogram.len(),
+            with.latency_histogram.len()
+        );
+    }
+}
@@ -2,4 +2,4 @@ pub mod api_debug;
 pub mod load_test;
 
 pub use api_debug::{ApiDebugResult, ApiDebugUseCase, ApiDebugUseCaseImpl};
-pub use load_test::{LoadTestUseCase, LoadTestUseCaseImpl};
+pub use load_test::{LoadTestControl, LoadTestUseCase, LoadTestUseCaseImpl};
@@ -0,0 +1,435 @@
+//! 响应检查
+//!
+//! 对每个响应做声明式检查（状态码、响应头、响应体、JSON 路径、JSON Schema、延迟），
+//! 任一检查失败的请求计为失败，即使状态码是 2xx。
+
+use super::HttpResponse;
+use crate::domain::value_objects::JsonPath;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
+/// 检查规则
+#[derive(Debug, Clone)]
+pub enum CheckRule {
+    /// 状态码属于给定集合
+    Status(Vec<u16>),
+    /// 响应头存在（忽略大小写），设置 `equals` 时还要求值相等
+    Header {
+        name: String,
+        equals: Option<String>,
+    },
+    /// 响应体包含给定文本
+    BodyContains(String),
+    /// 响应体按 JSON 解析后路径上的值等于 `equals`
+    Json { path: JsonPath, equals: Value },
+    /// 响应体符合 JSON Schema
+    JsonSchema(JsonSchema),
+    /// 延迟不超过给定值
+    MaxLatency(Duration),
+}
+
+/// 编译后的 JSON Schema，保留原文用于序列化
+#[derive(Debug, Clone)]
+pub struct JsonSchema {
+    schema: Value,
+    validator: jsonschema::Validator,
+}
+
+impl JsonSchema {
+    pub fn new(schema: Value) -> Result<Self, String> {
+        let validator = jsonschema::validator_for(&schema).map_err(|e| e.to_string())?;
+        Ok(Self { schema, validator })
+    }
+
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validator.is_valid(instance)
+    }
+}
+
+impl fmt::Display for CheckRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status(statuses) => {
+                let statuses: Vec<String> = statuses.iter().map(u16::to_string).collect();
+                write!(f, "status in [{}]", statuses.join(", "))
+            }
+            Self::Header {
+                name,
+                equals: Some(value),
+            } => write!(f, "header {} == {}", name, value),
+            Self::Header { name, equals: None } => write!(f, "header {}", name),
+            Self::BodyContains(text) => write!(f, "body contains {:?}", text),
+            Self::Json { path, equals } => write!(f, "{} == {}", path.as_str(), equals),
+            Self::JsonSchema(_) => write!(f, "json schema"),
+            Self::MaxLatency(latency) => write!(f, "latency <= {:?}", latency),
+        }
+    }
+}
+
+/// 场景文件中的写法：`{ status: [200, 201] }`、`{ header: ETag }`、
+/// `{ header: Content-Type, equals: application/json }`、`{ body_contains: ok }`、
+/// `{ json: "$.ok", equals: true }`、`{ json_schema: {...} }` 或 `{ max_latency_ms: 300 }`，
+/// 只能设置一种规则，可选 `name`
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckRepr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<StatusSet>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<JsonPath>,
+    /// 显式写出的 `equals: null` 表示期望值为 null，而不是未设置
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    equals: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_latency_ms: Option<u64>,
+}
+
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// 单个状态码或状态码列表
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StatusSet {
+    One(u16),
+    Many(Vec<u16>),
+}
+
+/// 一条命名的检查，未命名时以规则本身作为名称
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "CheckRepr", into = "CheckRepr")]
+pub struct Check {
+    pub name: String,
+    pub rule: CheckRule,
+}
+
+impl Check {
+    pub fn new(rule: CheckRule) -> Self {
+        Self {
+            name: rule.to_string(),
+            rule,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+impl TryFrom<CheckRepr> for Check {
+    type Error = String;
+
+    fn try_from(repr: CheckRepr) -> Result<Self, Self::Error> {
+        let rule = match (
+            repr.status,
+            repr.header,
+            repr.body_contains,
+            repr.json,
+            repr.json_schema,
+            repr.max_latency_ms,
+        ) {
+            (Some(status), None, None, None, None, None) => {
+                if repr.equals.is_some() {
+                    return Err("equals 只能与 header 或 json 一起使用".to_string());
+                }
+                CheckRule::Status(match status {
+                    StatusSet::One(status) => vec![status],
+                    StatusSet::Many(statuses) if !statuses.is_empty() => statuses,
+                    StatusSet::Many(_) => return Err("status 至少需要一个状态码".to_string()),
+                })
+            }
+            (None, Some(name), None, None, None, None) => CheckRule::Header {
+                name,
+                equals: match repr.equals {
+                    None => None,
+                    Some(Value::String(value)) => Some(value),
+                    Some(other) => Some(other.to_string()),
+                },
+            },
+            (None, None, Some(text), None, None, None) if repr.equals.is_none() => {
+                CheckRule::BodyContains(text)
+            }
+            (None, None, None, Some(path), None, None) => CheckRule::Json {
+                path,
+                equals: repr
+                    .equals
+                    .ok_or_else(|| "json 检查需要设置 equals".to_string())?,
+            },
+            (None, None, None, None, Some(schema), None) if repr.equals.is_none() => {
+                CheckRule::JsonSchema(JsonSchema::new(schema)?)
+            }
+            (None, None, None, None, None, Some(ms)) if repr.equals.is_none() => {
+                CheckRule::MaxLatency(Duration::from_millis(ms))
+            }
+            (None, None, None, None, None, None) => {
+                return Err(
+                    "检查必须设置 status、header、body_contains、json、json_schema、max_latency_ms 之一"
+                        .to_string(),
+                );
+            }
+            _ => {
+                return Err(
+                    "检查只能设置一种规则，equals 只能与 header 或 json 一起使用".to_string(),
+                );
+            }
+        };
+
+        let check = Self::new(rule);
+        Ok(match repr.name {
+            Some(name) => check.with_name(name),
+            None => check,
+        })
+    }
+}
+
+impl From<Check> for CheckRepr {
+    fn from(check: Check) -> Self {
+        let mut repr = Self {
+            name: Some(check.name),
+            ..Self::default()
+        };
+        match check.rule {
+            CheckRule::Status(statuses) => repr.status = Some(StatusSet::Many(statuses)),
+            CheckRule::Header { name, equals } => {
+                repr.header = Some(name);
+                repr.equals = equals.map(Value::String);
+            }
+            CheckRule::BodyContains(text) => repr.body_contains = Some(text),
+            CheckRule::Json { path, equals } => {
+                repr.json = Some(path);
+                repr.equals = Some(equals);
+            }
+            CheckRule::JsonSchema(schema) => repr.json_schema = Some(schema.schema),
+            CheckRule::MaxLatency(latency) => {
+                repr.max_latency_ms = Some(latency.as_millis() as u64)
+            }
+        }
+        repr
+    }
+}
+
+/// 对响应执行全部检查，按顺序返回每个检查是否通过
+///
+/// 没有检查时不读取响应体，响应体最多按 JSON 解析一次。
+pub fn run_checks(checks: &[Check], response: &HttpResponse, latency: Duration) -> Vec<bool> {
+    if checks.is_empty() {
+        return Vec::new();
+    }
+    let body = response
+        .body
+        .as_ref()
+        .map(|b| b.content())
+        .unwrap_or_default();
+    let mut json: Option<Option<Value>> = None;
+
+    checks
+        .iter()
+        .map(|check| match &check.rule {
+            CheckRule::Status(statuses) => statuses.contains(&response.status),
+            CheckRule::Header { name, equals } => {
+                match (response.headers.get_ignore_case(name), equals) {
+                    (Some(value), Some(expected)) => value == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            }
+            CheckRule::BodyContains(text) => body.contains(text.as_str()),
+            CheckRule::Json { path, equals } => json
+                .get_or_insert_with(|| serde_json::from_str(&body).ok())
+                .as_ref()
+                .and_then(|value| path.select(value))
+                .is_some_and(|value| value == equals),
+            CheckRule::JsonSchema(schema) => json
+                .get_or_insert_with(|| serde_json::from_str(&body).ok())
+                .as_ref()
+                .is_some_and(|value| schema.is_valid(value)),
+            CheckRule::MaxLatency(max) => latency <= *max,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{Body, Headers};
+    use serde_json::json;
+
+    fn check(repr: Value) -> Result<Check, String> {
+        serde_json::from_value(repr).map_err(|e| e.to_string())
+    }
+
+    fn response(status: u16, body: Option<&str>) -> HttpResponse {
+        let mut headers = Headers::new();
+        headers.insert("Content-Type", "application/json");
+        headers.insert("ETag", "\"v1\"");
+        HttpResponse {
+            status,
+            headers,
+            body: body.map(Body::json),
+            body_size: body.map_or(0, |body| body.len() as u64),
+            duration: Duration::from_millis(20),
+            timestamp: chrono::Utc::now(),
+            timing: Default::default(),
+        }
+    }
+
+    fn passes(check_repr: Value, response: &HttpResponse) -> bool {
+        let check = check(check_repr).unwrap();
+        run_checks(&[check], response, response.duration)[0]
+    }
+
+    #[test]
+    fn parses_each_rule_kind() {
+        let status = check(json!({ "status": 200 })).unwrap();
+        assert!(matches!(status.rule, CheckRule::Status(ref s) if s == &[200]));
+        assert_eq!(status.name, "status in [200]");
+
+        let header = check(json!({ "header": "X-Count", "equals": 3, "name": "count" })).unwrap();
+        assert_eq!(header.name, "count");
+        assert!(matches!(
+            header.rule,
+            CheckRule::Header { ref name, equals: Some(ref v) } if name == "X-Count" && v == "3"
+        ));
+
+        assert!(matches!(
+            check(json!({ "body_contains": "ok" })).unwrap().rule,
+            CheckRule::BodyContains(_)
+        ));
+        assert_eq!(
+            check(json!({ "json": "$.ok", "equals": true }))
+                .unwrap()
+                .name,
+            "$.ok == true"
+        );
+        assert!(matches!(
+            check(json!({ "json_schema": { "type": "object" } }))
+                .unwrap()
+                .rule,
+            CheckRule::JsonSchema(_)
+        ));
+        assert!(matches!(
+            check(json!({ "max_latency_ms": 300 })).unwrap().rule,
+            CheckRule::MaxLatency(latency) if latency == Duration::from_millis(300)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_checks() {
+        for invalid in [
+            json!({}),
+            json!({ "status": 200, "header": "ETag" }),
+            json!({ "status": 200, "equals": 1 }),
+            json!({ "status": [] }),
+            json!({ "body_contains": "ok", "equals": "ok" }),
+            json!({ "json": "$.ok" }),
+            json!({ "json": "$..ok", "equals": true }),
+            json!({ "json_schema": { "type": 5 } }),
+            json!({ "max_latency": 300 }),
+        ] {
+            assert!(check(invalid.clone()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_serde() {
+        let original = check(json!({ "json": "$.user.id", "equals": 7, "name": "user" })).unwrap();
+        let restored = check(serde_json::to_value(&original).unwrap()).unwrap();
+        assert_eq!(restored.name, "user");
+        assert_eq!(restored.rule.to_string(), original.rule.to_string());
+    }
+
+    #[test]
+    fn evaluates_status_header_body_and_latency() {
+        let ok = response(200, Some(r#"{"ok": true}"#));
+        assert!(passes(json!({ "status": [200, 201] }), &ok));
+        assert!(!passes(json!({ "status": 201 }), &ok));
+        assert!(passes(json!({ "header": "etag" }), &ok));
+        assert!(passes(json!({ "header": "etag", "equals": "\"v1\"" }), &ok));
+        assert!(!passes(
+            json!({ "header": "etag", "equals": "\"v2\"" }),
+            &ok
+        ));
+        assert!(!passes(json!({ "header": "Location" }), &ok));
+        assert!(passes(json!({ "body_contains": "\"ok\"" }), &ok));
+        assert!(!passes(json!({ "body_contains": "error" }), &ok));
+        assert!(passes(json!({ "max_latency_ms": 20 }), &ok));
+        assert!(!passes(json!({ "max_latency_ms": 19 }), &ok));
+    }
+
+    #[test]
+    fn fails_a_success_status_with_an_error_payload() {
+        let error = response(
+            200,
+            Some(r#"{"ok": false, "error": {"code": 42, "detail": null}}"#),
+        );
+        assert!(passes(json!({ "status": 200 }), &error));
+        assert!(!passes(json!({ "json": "$.ok", "equals": true }), &error));
+        assert!(passes(
+            json!({ "json": "$.error.code", "equals": 42 }),
+            &error
+        ));
+        assert!(passes(
+            json!({ "json": "$.error.detail", "equals": null }),
+            &error
+        ));
+        assert!(!passes(
+            json!({ "json": "$.missing", "equals": null }),
+            &error
+        ));
+
+        let schema = json!({
+            "json_schema": {
+                "type": "object",
+                "required": ["ok"],
+                "properties": { "ok": { "const": true } }
+            }
+        });
+        assert!(passes(
+            schema.clone(),
+            &response(200, Some(r#"{"ok": true}"#))
+        ));
+        assert!(!passes(schema, &error));
+    }
+
+    #[test]
+    fn json_checks_fail_without_a_json_body() {
+        let checks = [
+            check(json!({ "json": "$.ok", "equals": true })).unwrap(),
+            check(json!({ "json_schema": { "type": "object" } })).unwrap(),
+            check(json!({ "body_contains": "" })).unwrap(),
+        ];
+        let latency = Duration::ZERO;
+        assert_eq!(
+            run_checks(&checks, &response(200, Some("<html>")), latency),
+            [false, false, true]
+        );
+        assert_eq!(
+            run_checks(&checks, &response(204, None), latency),
+            [false, false, true]
+        );
+        assert!(run_checks(&[], &response(200, None), latency).is_empty());
+    }
+}
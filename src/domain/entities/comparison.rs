@@ -0,0 +1,405 @@
+//! 两次压测结果的对比
+//!
+//! 对比吞吐、错误率和各延迟分位在基线和本次结果之间的变化。某个指标变差超过容差，
+//! 并且在统计上可信（不是样本太少造成的波动）时判定为退化。
+
+use super::{LoadTestResult, ThresholdMetric};
+use crate::domain::value_objects::LatencyHistogram;
+use std::fmt;
+use std::str::FromStr;
+
+/// 默认对比的指标
+///
+/// 最小和最大延迟是单个样本的极值，波动很大，不在默认列表中。
+pub const DEFAULT_COMPARISON_METRICS: [ThresholdMetric; 8] = [
+    ThresholdMetric::Qps,
+    ThresholdMetric::ErrorRate,
+    ThresholdMetric::AvgLatency,
+    ThresholdMetric::Percentile(50.0),
+    ThresholdMetric::Percentile(90.0),
+    ThresholdMetric::Percentile(95.0),
+    ThresholdMetric::Percentile(99.0),
+    ThresholdMetric::Percentile(99.9),
+];
+
+/// 显著性检验使用的双侧 95% 置信水平对应的 z 值
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// 某个指标允许变差的幅度
+///
+/// 比例类指标（错误率、成功率）为绝对差值，`error_rate=0.5%` 表示错误率最多上升 0.5 个百分点；
+/// 其余指标为相对基线的比例，`p99=10%` 表示 P99 最多比基线慢 10%。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub metric: ThresholdMetric,
+    pub value: f64,
+}
+
+impl Tolerance {
+    /// 未指定容差时的默认值：比例类指标 1 个百分点，其余指标 10%
+    pub fn default_for(metric: ThresholdMetric) -> Self {
+        let value = if metric.is_ratio() { 0.01 } else { 0.10 };
+        Self { metric, value }
+    }
+}
+
+impl FromStr for Tolerance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (metric, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("容差格式应为 指标=值，实际为: {}", s))?;
+        let metric: ThresholdMetric = metric.trim().parse()?;
+        let value = value.trim();
+        let value = match value.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f64>().map(|v| v / 100.0),
+            None => value.parse::<f64>(),
+        }
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(|| format!("无效的容差值: {}", s))?;
+        Ok(Self { metric, value })
+    }
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}%", self.metric, self.value * 100.0)
+    }
+}
+
+/// 单个指标的对比结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricComparison {
+    pub metric: ThresholdMetric,
+    /// 基线和本次的取值，单位与阈值相同；没有请求时为 `None`
+    pub baseline: Option<f64>,
+    pub current: Option<f64>,
+    pub tolerance: Tolerance,
+    /// 差异在统计上可信
+    pub significant: bool,
+    /// 变差超过容差且差异可信
+    pub regressed: bool,
+}
+
+impl MetricComparison {
+    /// 变化量：比例类指标为绝对差值，其余为相对基线的比例；正数表示数值增大
+    pub fn change(&self) -> Option<f64> {
+        let (baseline, current) = (self.baseline?, self.current?);
+        if self.metric.is_ratio() {
+            Some(current - baseline)
+        } else if baseline == 0.0 {
+            (current == 0.0).then_some(0.0)
+        } else {
+            Some((current - baseline) / baseline)
+        }
+    }
+
+    /// 变差的幅度，变好时为负数
+    fn worsening(&self) -> Option<f64> {
+        let change = match self.change() {
+            Some(change) => change,
+            // 基线为 0 而本次不为 0
+            None if self.baseline.is_some() && self.current.is_some() => f64::INFINITY,
+            None => return None,
+        };
+        Some(if self.metric.higher_is_better() {
+            -change
+        } else {
+            change
+        })
+    }
+
+    /// 变化量的显示文本
+    pub fn format_change(&self) -> String {
+        match self.change() {
+            Some(change) if self.metric.is_ratio() => format!("{:+.2}pp", change * 100.0),
+            Some(change) => format!("{:+.1}%", change * 100.0),
+            None if self.baseline.is_some() && self.current.is_some() => "+∞".to_string(),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// 对比基线和本次结果
+///
+/// 默认对比 `DEFAULT_COMPARISON_METRICS`，`tolerances` 中的指标覆盖默认容差，
+/// 不在默认列表中的指标追加到末尾。吞吐和显式指定的最小、最大延迟没有显著性检验，
+/// 变差超过容差即判为退化。
+pub fn compare_results(
+    baseline: &LoadTestResult,
+    current: &LoadTestResult,
+    tolerances: &[Tolerance],
+) -> Vec<MetricComparison> {
+    let mut selected: Vec<Tolerance> = DEFAULT_COMPARISON_METRICS
+        .iter()
+        .map(|&metric| Tolerance::default_for(metric))
+        .collect();
+    for tolerance in tolerances {
+        match selected.iter_mut().find(|t| t.metric == tolerance.metric) {
+            Some(existing) => *existing = *tolerance,
+            None => selected.push(*tolerance),
+        }
+    }
+
+    selected
+        .into_iter()
+        .map(|tolerance| {
+            let metric = tolerance.metric;
+            let mut comparison = MetricComparison {
+                metric,
+                baseline: metric.observe(baseline),
+                current: metric.observe(current),
+                tolerance,
+                significant: is_significant(metric, baseline, current),
+                regressed: false,
+            };
+            comparison.regressed = comparison.significant
+                && comparison
+                    .worsening()
+                    .is_some_and(|worsening| worsening > tolerance.value);
+            comparison
+        })
+        .collect()
+}
+
+/// 判断两次结果在该指标上的差异是否超出随机波动
+///
+/// 分位数比较两边的置信区间是否重叠；平均延迟和比例用 z 检验；
+/// 吞吐和最值没有可用的方差信息，总是视为可信，是否退化只看容差。
+/// 旧版本导出的结果没有直方图，其分位和平均延迟同样只看容差。
+fn is_significant(
+    metric: ThresholdMetric,
+    baseline: &LoadTestResult,
+    current: &LoadTestResult,
+) -> bool {
+    if baseline.total_requests == 0 || current.total_requests == 0 {
+        return false;
+    }
+    let without_histogram =
+        baseline.latency_histogram.is_empty() || current.latency_histogram.is_empty();
+    match metric {
+        ThresholdMetric::Percentile(_) | ThresholdMetric::AvgLatency if without_histogram => true,
+        ThresholdMetric::Percentile(p) => {
+            let (base_low, base_high) = quantile_interval(&baseline.latency_histogram, p / 100.0);
+            let (cur_low, cur_high) = quantile_interval(&current.latency_histogram, p / 100.0);
+            cur_low > base_high || cur_high < base_low
+        }
+        ThresholdMetric::AvgLatency => {
+            let (m1, v1, n1) = mean_and_variance(&baseline.latency_histogram);
+            let (m2, v2, n2) = mean_and_variance(&current.latency_histogram);
+            let se = (v1 / n1 + v2 / n2).sqrt();
+            if se == 0.0 {
+                m1 != m2
+            } else {
+                ((m2 - m1) / se).abs() > CONFIDENCE_Z
+            }
+        }
+        ThresholdMetric::ErrorRate => proportion_differs(
+            baseline.failed_requests,
+            baseline.total_requests,
+            current.failed_requests,
+            current.total_requests,
+        ),
+        ThresholdMetric::SuccessRate => proportion_differs(
+            baseline.successful_requests,
+            baseline.total_requests,
+            current.successful_requests,
+            current.total_requests,
+        ),
+        ThresholdMetric::MinLatency | ThresholdMetric::MaxLatency | ThresholdMetric::Qps => true,
+    }
+}
+
+/// 分位数的 95% 置信区间（毫秒），按二项分布估计秩的范围
+fn quantile_interval(histogram: &LatencyHistogram, quantile: f64) -> (f64, f64) {
+    let n = histogram.len() as f64;
+    let spread = CONFIDENCE_Z * (quantile * (1.0 - quantile) / n).sqrt();
+    let millis = |q: f64| histogram.value_at_quantile(q.clamp(0.0, 1.0)).as_secs_f64() * 1000.0;
+    (millis(quantile - spread), millis(quantile + spread))
+}
+
+/// 直方图的均值、方差（毫秒）和样本数
+fn mean_and_variance(histogram: &LatencyHistogram) -> (f64, f64, f64) {
+    let n = histogram.len() as f64;
+    let millis = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let mean = histogram
+        .iter_recorded()
+        .map(|(value, count)| millis(value) * count as f64)
+        .sum::<f64>()
+        / n;
+    let variance = histogram
+        .iter_recorded()
+        .map(|(value, count)| (millis(value) - mean).powi(2) * count as f64)
+        .sum::<f64>()
+        / n;
+    (mean, variance, n)
+}
+
+/// 两个比例的 z 检验
+fn proportion_differs(x1: u64, n1: u64, x2: u64, n2: u64) -> bool {
+    let (n1, n2) = (n1 as f64, n2 as f64);
+    let (p1, p2) = (x1 as f64 / n1, x2 as f64 / n2);
+    let pooled = (x1 + x2) as f64 / (n1 + n2);
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    if se == 0.0 {
+        return p1 != p2;
+    }
+    ((p2 - p1) / se).abs() > CONFIDENCE_Z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn result_with(latencies_ms: &[u64], failed: usize, elapsed_secs: u64) -> LoadTestResult {
+        let mut result = LoadTestResult::default();
+        for (i, &ms) in latencies_ms.iter().enumerate() {
+            result.add_response(200, Duration::from_millis(ms), i >= failed);
+        }
+        result.elapsed = Duration::from_secs(elapsed_secs);
+        result.calculate_qps();
+        result.calculate_percentiles();
+        result
+    }
+
+    /// 1000 个请求，延迟在 `base_ms` 到 `base_ms + 9` 毫秒之间均匀分布
+    fn latencies(base_ms: u64) -> Vec<u64> {
+        (0..1000).map(|i| base_ms + i % 10).collect()
+    }
+
+    fn find(comparisons: &[MetricComparison], metric: ThresholdMetric) -> &MetricComparison {
+        comparisons.iter().find(|c| c.metric == metric).unwrap()
+    }
+
+    #[test]
+    fn parses_tolerances() {
+        let tolerance: Tolerance = "p99=10%".parse().unwrap();
+        assert_eq!(tolerance.metric, ThresholdMetric::Percentile(99.0));
+        assert!((tolerance.value - 0.10).abs() < 1e-12);
+        let tolerance: Tolerance = "error_rate = 0.5%".parse().unwrap();
+        assert!((tolerance.value - 0.005).abs() < 1e-12);
+        assert_eq!("qps=0.2".parse::<Tolerance>().unwrap().value, 0.2);
+        assert_eq!(
+            "p99=-1".parse::<Tolerance>().unwrap_err(),
+            "无效的容差值: p99=-1"
+        );
+        assert!("p99".parse::<Tolerance>().is_err());
+    }
+
+    #[test]
+    fn identical_results_do_not_regress() {
+        let baseline = result_with(&latencies(10), 10, 10);
+        let comparisons = compare_results(&baseline, &baseline.clone(), &[]);
+        assert_eq!(comparisons.len(), DEFAULT_COMPARISON_METRICS.len());
+        for comparison in &comparisons {
+            assert!(!comparison.regressed, "{}", comparison.metric);
+            assert_eq!(comparison.change(), Some(0.0));
+        }
+        assert!(!find(&comparisons, ThresholdMetric::Percentile(50.0)).significant);
+        assert!(!find(&comparisons, ThresholdMetric::AvgLatency).significant);
+    }
+
+    #[test]
+    fn detects_latency_throughput_and_error_regressions() {
+        let baseline = result_with(&latencies(10), 0, 10);
+        let current = result_with(&latencies(20), 50, 20);
+        let comparisons = compare_results(&baseline, &current, &[]);
+        for metric in [
+            ThresholdMetric::Percentile(50.0),
+            ThresholdMetric::Percentile(99.0),
+            ThresholdMetric::AvgLatency,
+            ThresholdMetric::Qps,
+            ThresholdMetric::ErrorRate,
+        ] {
+            let comparison = find(&comparisons, metric);
+            assert!(comparison.significant && comparison.regressed, "{}", metric);
+        }
+        let qps = find(&comparisons, ThresholdMetric::Qps);
+        assert_eq!(qps.format_change(), "-50.0%");
+        assert_eq!(
+            find(&comparisons, ThresholdMetric::ErrorRate).format_change(),
+            "+5.00pp"
+        );
+    }
+
+    #[test]
+    fn ignores_differences_within_noise() {
+        let baseline = result_with(&[5, 10, 15, 20, 25], 0, 1);
+        let current = result_with(&[6, 12, 18, 24, 30], 0, 1);
+        let comparisons = compare_results(&baseline, &current, &[]);
+        let p50 = find(&comparisons, ThresholdMetric::Percentile(50.0));
+        assert!(p50.change().unwrap() > p50.tolerance.value);
+        assert!(!p50.significant && !p50.regressed);
+        assert!(!find(&comparisons, ThresholdMetric::AvgLatency).regressed);
+    }
+
+    #[test]
+    fn tolerances_override_defaults_and_add_metrics() {
+        let baseline = result_with(&latencies(10), 0, 10);
+        let current = result_with(&latencies(20), 0, 10);
+        let tolerances = ["p50=200%".parse().unwrap(), "max=10%".parse().unwrap()];
+        let comparisons = compare_results(&baseline, &current, &tolerances);
+        assert_eq!(comparisons.len(), DEFAULT_COMPARISON_METRICS.len() + 1);
+        assert!(!find(&comparisons, ThresholdMetric::Percentile(50.0)).regressed);
+        let max = comparisons.last().unwrap();
+        assert_eq!(max.metric, ThresholdMetric::MaxLatency);
+        assert!(max.regressed);
+    }
+
+    #[test]
+    fn reads_results_exported_before_newer_fields_existed() {
+        let old = r#"{
+            "total_requests": 1000,
+            "successful_requests": 990,
+            "failed_requests": 10,
+            "status_2xx": 990,
+            "status_4xx": 0,
+            "status_5xx": 10,
+            "total_duration": { "secs": 12, "nanos": 0 },
+            "min_latency": { "secs": 0, "nanos": 10000000 },
+            "max_latency": { "secs": 0, "nanos": 19000000 },
+            "avg_latency": { "secs": 0, "nanos": 14500000 },
+            "p50_latency": { "secs": 0, "nanos": 14000000 },
+            "p90_latency": { "secs": 0, "nanos": 18000000 },
+            "p95_latency": { "secs": 0, "nanos": 19000000 },
+            "p99_latency": { "secs": 0, "nanos": 19000000 },
+            "qps": 100.0
+        }"#;
+        let baseline: LoadTestResult = serde_json::from_str(old).unwrap();
+        assert_eq!(baseline.total_requests, 1000);
+        assert_eq!(baseline.unsent_requests, 0);
+        assert!(baseline.errors.is_empty() && baseline.checks.is_empty());
+        assert!(!baseline.interrupted && !baseline.data_exhausted);
+        assert!(baseline.time_series.is_empty());
+
+        let minimal: LoadTestResult = serde_json::from_str("{}").unwrap();
+        assert_eq!(minimal.total_requests, 0);
+        assert_eq!(minimal.min_latency, Duration::MAX);
+
+        // 没有直方图时分位取导出时的值，差异只按容差判断
+        let current = result_with(&latencies(10), 10, 10);
+        let comparisons = compare_results(&baseline, &current, &[]);
+        assert_eq!(comparisons.len(), DEFAULT_COMPARISON_METRICS.len());
+        let p99 = find(&comparisons, ThresholdMetric::Percentile(99.0));
+        assert_eq!(p99.baseline, Some(19.0));
+        assert!(p99.significant && !p99.regressed);
+        // 更早的版本没有导出 p99.9，取最大延迟
+        assert_eq!(
+            find(&comparisons, ThresholdMetric::Percentile(99.9)).baseline,
+            Some(19.0)
+        );
+        assert_eq!(
+            find(&comparisons, ThresholdMetric::ErrorRate).change(),
+            Some(0.0)
+        );
+        assert!(comparisons.iter().all(|comparison| !comparison.regressed));
+
+        let slower = result_with(&latencies(30), 10, 10);
+        let comparisons = compare_results(&baseline, &slower, &[]);
+        assert!(find(&comparisons, ThresholdMetric::Percentile(50.0)).regressed);
+        assert!(find(&comparisons, ThresholdMetric::AvgLatency).regressed);
+    }
+}
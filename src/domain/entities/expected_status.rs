@@ -0,0 +1,164 @@
+//! 期望状态码
+//!
+//! 决定收到的响应是否计为成功：状态码不在期望范围内的响应计为失败。
+//! 未设置时 2xx 和 3xx 视为成功。
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 未设置期望状态码时视为成功的范围
+const DEFAULT_EXPECTED: std::ops::Range<u16> = 200..400;
+
+/// 一个期望的状态码，或 `3xx` 这样的一类状态码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "StatusRepr", into = "StatusRepr")]
+pub enum ExpectedStatus {
+    Code(u16),
+    /// 状态码的百位数字
+    Class(u8),
+}
+
+impl ExpectedStatus {
+    pub fn matches(&self, status: u16) -> bool {
+        match self {
+            Self::Code(code) => status == *code,
+            Self::Class(class) => status / 100 == u16::from(*class),
+        }
+    }
+}
+
+/// 状态码是否在期望范围内，`expected` 为空时按默认的 2xx 和 3xx 判断
+pub fn is_expected_status(expected: &[ExpectedStatus], status: u16) -> bool {
+    if expected.is_empty() {
+        DEFAULT_EXPECTED.contains(&status)
+    } else {
+        expected.iter().any(|e| e.matches(status))
+    }
+}
+
+impl FromStr for ExpectedStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lower = s.to_lowercase();
+        if let Some(class) = lower.strip_suffix("xx")
+            && let Ok(class @ 1..=5) = class.parse::<u8>()
+        {
+            return Ok(Self::Class(class));
+        }
+        match s.parse::<u16>() {
+            Ok(code @ 100..=599) => Ok(Self::Code(code)),
+            _ => Err(format!("Invalid expected status: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ExpectedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "{}", code),
+            Self::Class(class) => write!(f, "{}xx", class),
+        }
+    }
+}
+
+/// 配置中的写法：`302` 或 `"3xx"`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StatusRepr {
+    Code(u16),
+    Text(String),
+}
+
+impl TryFrom<StatusRepr> for ExpectedStatus {
+    type Error = String;
+
+    fn try_from(repr: StatusRepr) -> Result<Self, Self::Error> {
+        match repr {
+            StatusRepr::Code(code) => code.to_string().parse(),
+            StatusRepr::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<ExpectedStatus> for StatusRepr {
+    fn from(status: ExpectedStatus) -> Self {
+        match status {
+            ExpectedStatus::Code(code) => Self::Code(code),
+            ExpectedStatus::Class(_) => Self::Text(status.to_string()),
+        }
+    }
+}
+
+/// 场景文件中既可以写单个状态码，也可以写列表
+pub(crate) fn deserialize_one_or_many<'de, D>(
+    deserializer: D,
+) -> Result<Vec<ExpectedStatus>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ExpectedStatus),
+        Many(Vec<ExpectedStatus>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(status) => vec![status],
+        OneOrMany::Many(statuses) => statuses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        #[serde(default, deserialize_with = "deserialize_one_or_many")]
+        expect: Vec<ExpectedStatus>,
+    }
+
+    #[test]
+    fn parses_codes_and_classes() {
+        assert_eq!("302".parse(), Ok(ExpectedStatus::Code(302)));
+        assert_eq!(" 3xx ".parse(), Ok(ExpectedStatus::Class(3)));
+        assert_eq!("4XX".parse(), Ok(ExpectedStatus::Class(4)));
+        for invalid in ["600", "99", "6xx", "0xx", "x", ""] {
+            assert!(invalid.parse::<ExpectedStatus>().is_err(), "{}", invalid);
+        }
+        assert_eq!(ExpectedStatus::Class(2).to_string(), "2xx");
+    }
+
+    #[test]
+    fn matches_expected_statuses() {
+        assert!(is_expected_status(&[], 204));
+        assert!(is_expected_status(&[], 302));
+        assert!(!is_expected_status(&[], 404));
+        let expected = [ExpectedStatus::Code(404), ExpectedStatus::Class(2)];
+        assert!(is_expected_status(&expected, 404));
+        assert!(is_expected_status(&expected, 201));
+        assert!(!is_expected_status(&expected, 302));
+    }
+
+    #[test]
+    fn deserializes_one_or_many() {
+        let one: Config = serde_json::from_str(r#"{"expect": 404}"#).unwrap();
+        assert_eq!(one.expect, [ExpectedStatus::Code(404)]);
+        let many: Config = serde_json::from_str(r#"{"expect": [200, "3xx"]}"#).unwrap();
+        assert_eq!(
+            many.expect,
+            [ExpectedStatus::Code(200), ExpectedStatus::Class(3)]
+        );
+        let none: Config = serde_json::from_str("{}").unwrap();
+        assert!(none.expect.is_empty());
+        assert!(serde_json::from_str::<Config>(r#"{"expect": 700}"#).is_err());
+        assert_eq!(
+            serde_json::to_string(&[ExpectedStatus::Code(200), ExpectedStatus::Class(3)]).unwrap(),
+            r#"[200,"3xx"]"#
+        );
+    }
+}
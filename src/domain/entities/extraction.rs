@@ -0,0 +1,235 @@
+//! 响应值提取
+//!
+//! 从响应中取出值存入虚拟用户的变量表，供后续请求的 `{{name}}` 占位符引用。
+
+use super::HttpResponse;
+use crate::domain::value_objects::{JsonPath, Variables};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 取值来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "ExtractSourceRepr", into = "ExtractSourceRepr")]
+pub enum ExtractSource {
+    /// 响应体按 JSON 解析后取路径上的值
+    Json(JsonPath),
+    /// 响应体上的正则匹配，取第 `group` 个捕获组（0 为整个匹配）
+    Regex { pattern: Regex, group: usize },
+    /// 响应头（忽略大小写）
+    Header(String),
+    /// `Set-Cookie` 中指定名称的 cookie 值
+    ///
+    /// 只能取到客户端返回的那个响应的响应头：跟随重定向时 302 等中间响应设置的 cookie 取不到。
+    Cookie(String),
+}
+
+/// 场景文件中的写法：`{ json: "$.token" }`、`{ regex: "id=(\\d+)", group: 1 }`、
+/// `{ header: ETag }` 或 `{ cookie: SID }`，只能设置一种来源
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExtractSourceRepr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<JsonPath>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cookie: Option<String>,
+}
+
+impl TryFrom<ExtractSourceRepr> for ExtractSource {
+    type Error = String;
+
+    fn try_from(repr: ExtractSourceRepr) -> Result<Self, Self::Error> {
+        let source = match (repr.json, repr.regex, repr.header, repr.cookie) {
+            (Some(path), None, None, None) => Self::Json(path),
+            (None, Some(pattern), None, None) => {
+                let pattern = Regex::new(&pattern).map_err(|e| e.to_string())?;
+                let group = repr
+                    .group
+                    .unwrap_or(if pattern.captures_len() > 1 { 1 } else { 0 });
+                if group >= pattern.captures_len() {
+                    return Err(format!("正则 {} 没有第 {} 个捕获组", pattern, group));
+                }
+                return Ok(Self::Regex { pattern, group });
+            }
+            (None, None, Some(name), None) => Self::Header(name),
+            (None, None, None, Some(name)) => Self::Cookie(name),
+            _ => return Err("提取规则必须且只能设置 json、regex、header、cookie 之一".to_string()),
+        };
+        if repr.group.is_some() {
+            return Err("group 只能与 regex 一起使用".to_string());
+        }
+        Ok(source)
+    }
+}
+
+impl From<ExtractSource> for ExtractSourceRepr {
+    fn from(source: ExtractSource) -> Self {
+        match source {
+            ExtractSource::Json(path) => Self {
+                json: Some(path),
+                ..Self::default()
+            },
+            ExtractSource::Regex { pattern, group } => Self {
+                regex: Some(pattern.as_str().to_string()),
+                group: Some(group),
+                ..Self::default()
+            },
+            ExtractSource::Header(name) => Self {
+                header: Some(name),
+                ..Self::default()
+            },
+            ExtractSource::Cookie(name) => Self {
+                cookie: Some(name),
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// 一条提取规则：把取到的值存入变量 `variable`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extractor {
+    pub variable: String,
+    pub source: ExtractSource,
+}
+
+impl Extractor {
+    pub fn new(variable: impl Into<String>, source: ExtractSource) -> Self {
+        Self {
+            variable: variable.into(),
+            source,
+        }
+    }
+}
+
+/// 依次执行提取规则并写入变量表，取不到值时返回对应的变量名
+///
+/// 响应体最多按 JSON 解析一次。
+pub fn extract_variables(
+    extractors: &[Extractor],
+    response: &HttpResponse,
+    variables: &mut Variables,
+) -> Result<(), String> {
+    let body = response
+        .body
+        .as_ref()
+        .map(|b| b.content())
+        .unwrap_or_default();
+    let mut json: Option<Option<serde_json::Value>> = None;
+
+    for extractor in extractors {
+        let value = match &extractor.source {
+            ExtractSource::Json(path) => json
+                .get_or_insert_with(|| serde_json::from_str(&body).ok())
+                .as_ref()
+                .and_then(|value| path.select_string(value)),
+            ExtractSource::Regex { pattern, group } => pattern
+                .captures(&body)
+                .and_then(|captures| captures.get(*group))
+                .map(|m| m.as_str().to_string()),
+            ExtractSource::Header(name) => response.headers.get_ignore_case(name).cloned(),
+            ExtractSource::Cookie(name) => response
+                .headers
+                .get_ignore_case("set-cookie")
+                .and_then(|cookies| find_cookie(cookies, name)),
+        };
+
+        match value {
+            Some(value) => {
+                variables.insert(extractor.variable.clone(), value);
+            }
+            None => return Err(extractor.variable.clone()),
+        }
+    }
+    Ok(())
+}
+
+/// 从换行分隔的 `Set-Cookie` 值中找出指定 cookie
+fn find_cookie(set_cookies: &str, name: &str) -> Option<String> {
+    set_cookies.lines().find_map(|cookie| {
+        let pair = cookie.split(';').next()?;
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{Body, Headers};
+    use std::time::Duration;
+
+    fn response(body: &str) -> HttpResponse {
+        let mut headers = Headers::new();
+        headers.append("ETag", "v1");
+        headers.append("Set-Cookie", "theme=dark; Path=/");
+        headers.append("Set-Cookie", "SID=s3cr3t; HttpOnly");
+        HttpResponse {
+            status: 200,
+            headers,
+            body: Some(Body::json(body)),
+            body_size: body.len() as u64,
+            duration: Duration::ZERO,
+            timestamp: chrono::Utc::now(),
+            timing: Default::default(),
+        }
+    }
+
+    fn extractor(variable: &str, source: &str) -> Extractor {
+        Extractor::new(variable, serde_json::from_str(source).unwrap())
+    }
+
+    #[test]
+    fn extracts_from_every_source() {
+        let extractors = [
+            extractor("token", r#"{ "json": "$.data.token" }"#),
+            extractor("id", r#"{ "regex": "\"id\": (\\d+)" }"#),
+            extractor("etag", r#"{ "header": "etag" }"#),
+            extractor("sid", r#"{ "cookie": "SID" }"#),
+        ];
+        let mut variables = Variables::new();
+        extract_variables(
+            &extractors,
+            &response(r#"{"data": {"token": "abc", "id": 42}}"#),
+            &mut variables,
+        )
+        .unwrap();
+        assert_eq!(variables["token"], "abc");
+        assert_eq!(variables["id"], "42");
+        assert_eq!(variables["etag"], "v1");
+        assert_eq!(variables["sid"], "s3cr3t");
+    }
+
+    #[test]
+    fn reports_the_variable_that_could_not_be_extracted() {
+        let extractors = [
+            extractor("theme", r#"{ "cookie": "theme" }"#),
+            extractor("missing", r#"{ "json": "$.nope" }"#),
+        ];
+        let mut variables = Variables::new();
+        let error = extract_variables(&extractors, &response("not json"), &mut variables);
+        assert_eq!(error, Err("missing".to_string()));
+        assert_eq!(variables["theme"], "dark");
+    }
+
+    #[test]
+    fn rejects_ambiguous_sources() {
+        for source in [
+            r#"{ "json": "$.a", "header": "b" }"#,
+            r#"{ "header": "b", "group": 1 }"#,
+            r#"{ "regex": "a(b)", "group": 2 }"#,
+            r#"{}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<ExtractSource>(source).is_err(),
+                "{}",
+                source
+            );
+        }
+    }
+}
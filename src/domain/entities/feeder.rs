@@ -0,0 +1,212 @@
+//! 数据源
+//!
+//! 把数据文件的每一行绑定给虚拟用户，行中的列作为变量供请求模板引用。
+
+use crate::domain::value_objects::Variables;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 取行策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedStrategy {
+    /// 第 N 个虚拟用户固定使用第 N 行（行数不足时回绕）
+    Sequential,
+    /// 每次迭代随机取一行
+    Random,
+    /// 每次迭代按顺序取下一行，取完后从头开始
+    #[default]
+    Circular,
+    /// 每次迭代按顺序取下一行，每行只用一次，取完后不再开始新的迭代
+    Unique,
+}
+
+impl FromStr for FeedStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sequential" => Ok(Self::Sequential),
+            "random" => Ok(Self::Random),
+            "circular" => Ok(Self::Circular),
+            "unique" => Ok(Self::Unique),
+            _ => Err(format!("Invalid feed strategy: {}", s)),
+        }
+    }
+}
+
+/// 场景文件中的数据源声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeederSpec {
+    /// CSV（首行为列名）、JSON Lines 或 JSON 数组文件，相对路径以场景文件所在目录为准
+    pub path: PathBuf,
+    #[serde(default)]
+    pub strategy: FeedStrategy,
+}
+
+/// 已加载的数据源，克隆后共享取行进度
+#[derive(Debug, Clone)]
+pub struct Feeder {
+    name: String,
+    columns: Vec<String>,
+    rows: Arc<Vec<Variables>>,
+    strategy: FeedStrategy,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl Feeder {
+    pub fn new(
+        name: impl Into<String>,
+        rows: Vec<Variables>,
+        strategy: FeedStrategy,
+    ) -> Result<Self, String> {
+        let name = name.into();
+        if rows.is_empty() {
+            return Err(format!("数据源 {} 没有数据", name));
+        }
+
+        let columns: BTreeSet<&String> = rows.iter().flat_map(|row| row.keys()).collect();
+        let columns = columns.into_iter().cloned().collect();
+
+        Ok(Self {
+            name,
+            columns,
+            rows: Arc::new(rows),
+            strategy,
+            cursor: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// 共享数据行、取行进度从头开始的副本，每次压测开始时使用
+    pub fn restart(&self) -> Self {
+        Self {
+            cursor: Arc::new(AtomicUsize::new(0)),
+            ..self.clone()
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn strategy(&self) -> FeedStrategy {
+        self.strategy
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// 为虚拟用户 `user` 的下一次迭代取一行，`Unique` 策略取完后返回 `None`
+    pub fn next_row(&self, user: usize) -> Option<&Variables> {
+        let len = self.rows.len();
+        let index = match self.strategy {
+            FeedStrategy::Sequential => user % len,
+            FeedStrategy::Random => rand::random_range(0..len),
+            FeedStrategy::Circular => self.cursor.fetch_add(1, Ordering::Relaxed) % len,
+            FeedStrategy::Unique => {
+                let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+                if index >= len {
+                    return None;
+                }
+                index
+            }
+        };
+        self.rows.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeder(strategy: FeedStrategy) -> Feeder {
+        let rows = ["a", "b", "c"]
+            .iter()
+            .map(|id| Variables::from([("id".to_string(), id.to_string())]))
+            .collect();
+        Feeder::new("ids", rows, strategy).unwrap()
+    }
+
+    fn ids(feeder: &Feeder, users: &[usize]) -> Vec<Option<String>> {
+        users
+            .iter()
+            .map(|&user| feeder.next_row(user).map(|row| row["id"].clone()))
+            .collect()
+    }
+
+    fn some(ids: &[&str]) -> Vec<Option<String>> {
+        ids.iter().map(|id| Some(id.to_string())).collect()
+    }
+
+    #[test]
+    fn sequential_binds_rows_to_users() {
+        let feeder = feeder(FeedStrategy::Sequential);
+        assert_eq!(
+            ids(&feeder, &[0, 1, 2, 3, 1]),
+            some(&["a", "b", "c", "a", "b"])
+        );
+    }
+
+    #[test]
+    fn circular_wraps_around() {
+        let feeder = feeder(FeedStrategy::Circular);
+        assert_eq!(
+            ids(&feeder, &[0, 0, 0, 0, 7]),
+            some(&["a", "b", "c", "a", "b"])
+        );
+    }
+
+    #[test]
+    fn unique_uses_each_row_once_across_clones() {
+        let feeder = feeder(FeedStrategy::Unique);
+        let shared = feeder.clone();
+        assert_eq!(ids(&feeder, &[0, 1]), some(&["a", "b"]));
+        assert_eq!(ids(&shared, &[2, 3]), vec![Some("c".to_string()), None]);
+        assert_eq!(feeder.next_row(0), None);
+        assert_eq!(ids(&feeder.restart(), &[0]), some(&["a"]));
+    }
+
+    #[test]
+    fn random_picks_existing_rows() {
+        let feeder = feeder(FeedStrategy::Random);
+        for id in ids(&feeder, &[0; 50]) {
+            assert!(matches!(id.as_deref(), Some("a" | "b" | "c")));
+        }
+    }
+
+    #[test]
+    fn collects_columns_and_rejects_empty_data() {
+        let rows = vec![
+            Variables::from([("id".to_string(), "1".to_string())]),
+            Variables::from([("name".to_string(), "x".to_string())]),
+        ];
+        let feeder = Feeder::new("rows", rows, FeedStrategy::default()).unwrap();
+        assert_eq!(feeder.columns(), ["id", "name"]);
+        assert_eq!(feeder.strategy(), FeedStrategy::Circular);
+        assert_eq!(
+            Feeder::new("empty", Vec::new(), FeedStrategy::Unique).unwrap_err(),
+            "数据源 empty 没有数据"
+        );
+    }
+
+    #[test]
+    fn parses_strategies() {
+        assert_eq!("Unique".parse(), Ok(FeedStrategy::Unique));
+        assert_eq!("sequential".parse(), Ok(FeedStrategy::Sequential));
+        assert!("shuffle".parse::<FeedStrategy>().is_err());
+    }
+}
@@ -1,5 +1,7 @@
-use crate::domain::value_objects::{Body, Headers, HttpMethod, Url};
+use crate::domain::value_objects::template::{self, Escape};
+use crate::domain::value_objects::{Body, Headers, HttpMethod, Url, Variables};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,13 +27,105 @@ impl Default for HttpRequest {
     }
 }
 
+impl HttpRequest {
+    /// 请求中引用的全部变量名（URL、请求头值和请求体）
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        template::placeholders(self.url.as_str())
+            .chain(
+                self.headers
+                    .iter()
+                    .flat_map(|(_, v)| template::placeholders(v)),
+            )
+            .chain(
+                self.body
+                    .iter()
+                    .filter_map(Body::as_str)
+                    .flat_map(template::placeholders),
+            )
+    }
+
+    /// 用变量替换 `{{name}}` 占位符，没有占位符时不复制请求
+    ///
+    /// URL 中的值做百分号编码，请求头中的值原样插入，请求体按 `Body::render` 转义；
+    /// `{{name | raw}}` 不转义。引用未定义的变量或替换后的 URL 无效时返回错误。
+    pub fn render(&self, variables: &Variables) -> Result<Cow<'_, HttpRequest>, String> {
+        if self.placeholders().next().is_none() {
+            return Ok(Cow::Borrowed(self));
+        }
+
+        let url = template::render(self.url.as_str(), variables, Escape::Url)?;
+        let mut headers = Headers::with_capacity(self.headers.len());
+        for (key, value) in self.headers.iter() {
+            headers.insert(
+                key.as_str(),
+                template::render(value, variables, Escape::None)?,
+            );
+        }
+        let body = match &self.body {
+            Some(body) => Some(body.render(variables)?),
+            None => None,
+        };
+
+        Ok(Cow::Owned(HttpRequest {
+            id: self.id,
+            url: Url::parse(&url).map_err(|e| e.to_string())?,
+            method: self.method,
+            headers,
+            body,
+            timeout: self.timeout,
+        }))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpResponse {
     pub status: u16,
     pub headers: Headers,
     pub body: Option<Body>,
+    /// 响应体的原始字节数（不含响应头），在按文本解码之前计数
+    #[serde(default)]
+    pub body_size: u64,
     pub duration: Duration,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub timing: RequestTiming,
+}
+
+/// 单个请求各阶段耗时
+///
+/// 建连相关阶段在复用已有连接或无法测量时为 `None`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestTiming {
+    /// DNS 解析
+    pub dns: Option<Duration>,
+    /// TCP 建连
+    pub connect: Option<Duration>,
+    /// TLS 握手
+    pub tls: Option<Duration>,
+    /// 从连接可用到收到响应头（首字节时间）
+    pub ttfb: Duration,
+    /// 读取响应体
+    pub transfer: Duration,
+}
+
+impl RequestTiming {
+    /// 建连阶段（DNS、TCP、TLS）的总耗时
+    pub fn setup(&self) -> Duration {
+        [self.dns, self.connect, self.tls]
+            .into_iter()
+            .flatten()
+            .sum()
+    }
+
+    /// 全部阶段的总耗时
+    pub fn total(&self) -> Duration {
+        self.setup() + self.ttfb + self.transfer
+    }
+
+    /// 是否新建了连接
+    pub fn is_new_connection(&self) -> bool {
+        self.connect.is_some()
+    }
 }
 
 impl HttpResponse {
@@ -47,3 +141,57 @@ impl HttpResponse {
         (500..600).contains(&self.status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_escapes_by_position() {
+        let mut headers = Headers::new();
+        headers.insert("X-User", "{{user}}");
+        let request = HttpRequest {
+            url: Url::parse("http://localhost/users/{{user}}?next={{next | raw}}").unwrap(),
+            method: HttpMethod::POST,
+            headers,
+            body: Some(Body::json(r#"{"user": "{{user}}"}"#)),
+            ..HttpRequest::default()
+        };
+        let variables = Variables::from([
+            ("user".to_string(), "a \"b\"/c".to_string()),
+            ("next".to_string(), "/home?x=1".to_string()),
+        ]);
+
+        let rendered = request.render(&variables).unwrap();
+        assert_eq!(
+            rendered.url.as_str(),
+            "http://localhost/users/a%20%22b%22%2Fc?next=/home?x=1"
+        );
+        assert_eq!(rendered.headers.get("X-User").unwrap(), "a \"b\"/c");
+        assert_eq!(
+            rendered.body.as_ref().unwrap().content(),
+            r#"{"user": "a \"b\"/c"}"#
+        );
+    }
+
+    #[test]
+    fn render_without_placeholders_borrows() {
+        let request = HttpRequest::default();
+        assert!(matches!(
+            request.render(&Variables::new()),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn render_reports_undefined_variables() {
+        let request = HttpRequest {
+            url: Url::parse("http://localhost/{{missing}}").unwrap(),
+            ..HttpRequest::default()
+        };
+        assert_eq!(
+            request.render(&Variables::new()).unwrap_err(),
+            "未定义的变量: missing"
+        );
+    }
+}
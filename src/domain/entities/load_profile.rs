@@ -0,0 +1,379 @@
+//! 负载曲线
+//!
+//! 一个测试由若干阶段组成，每个阶段在其时长内把目标并发数（或到达速率）
+//! 从上一阶段的目标线性过渡到本阶段的目标；时长为 0 的阶段表示直接跳变。
+
+use super::LoadTestState;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 阶段目标：闭环模式的并发用户数，或开环模式的到达速率（请求/秒）
+///
+/// 反序列化时除 `{ users: 100 }` 外也接受与命令行一致的 `100`、`500/s` 写法。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "StageTargetRepr")]
+pub enum StageTarget {
+    Users(usize),
+    Rate(f64),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TaggedStageTarget {
+    Users(usize),
+    Rate(f64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StageTargetRepr {
+    Users(usize),
+    Text(String),
+    Tagged(TaggedStageTarget),
+}
+
+impl TryFrom<StageTargetRepr> for StageTarget {
+    type Error = String;
+
+    fn try_from(repr: StageTargetRepr) -> Result<Self, Self::Error> {
+        match repr {
+            StageTargetRepr::Users(users) => Ok(Self::Users(users)),
+            StageTargetRepr::Text(text) => text.parse(),
+            StageTargetRepr::Tagged(TaggedStageTarget::Users(users)) => Ok(Self::Users(users)),
+            StageTargetRepr::Tagged(TaggedStageTarget::Rate(rate)) => Ok(Self::Rate(rate)),
+        }
+    }
+}
+
+impl FromStr for StageTarget {
+    type Err = String;
+
+    /// `100` 表示用户数，`500/s`、`6000/m` 表示速率
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('/') {
+            parse_rate(s).map(Self::Rate)
+        } else {
+            s.trim()
+                .parse()
+                .map(Self::Users)
+                .map_err(|_| format!("无效的用户数: {}", s))
+        }
+    }
+}
+
+/// 解析到达速率（请求/秒）：`N`、`N/s` 或 `N/m`
+pub fn parse_rate(s: &str) -> Result<f64, String> {
+    let (value, per_secs) = match s.split_once('/') {
+        Some((value, "s")) => (value, 1.0),
+        Some((value, "m")) => (value, 60.0),
+        Some((_, unit)) => return Err(format!("不支持的速率单位: {}", unit)),
+        None => (s, 1.0),
+    };
+    let value: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的速率: {}", s))?;
+    if value <= 0.0 {
+        return Err("速率必须大于 0".to_string());
+    }
+    Ok(value / per_secs)
+}
+
+impl StageTarget {
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Users(users) => *users as f64,
+            Self::Rate(rate) => *rate,
+        }
+    }
+
+    pub fn is_rate(&self) -> bool {
+        matches!(self, Self::Rate(_))
+    }
+}
+
+impl fmt::Display for StageTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Users(users) => write!(f, "{} users", users),
+            Self::Rate(rate) => write!(f, "{}/s", rate),
+        }
+    }
+}
+
+/// 负载阶段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadStage {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub duration_secs: u64,
+    pub target: StageTarget,
+}
+
+impl LoadStage {
+    pub fn new(duration_secs: u64, target: StageTarget) -> Self {
+        Self {
+            name: None,
+            duration_secs,
+            target,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_secs)
+    }
+
+    /// 阶段显示名称，未命名时使用序号
+    pub fn display_name(&self, index: usize) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("stage-{}", index + 1))
+    }
+}
+
+/// 由阶段组成的完整负载曲线
+#[derive(Debug, Clone)]
+pub struct LoadProfile {
+    stages: Vec<LoadStage>,
+    warmup: Duration,
+    /// 每个阶段开始时刻（秒），多一个元素表示结束时刻
+    boundaries: Vec<f64>,
+    /// 开环模式下每个阶段开始前累计的到达数
+    cumulative_arrivals: Vec<f64>,
+}
+
+impl LoadProfile {
+    /// 校验并创建负载曲线，所有阶段必须同为并发目标或同为速率目标
+    pub fn new(stages: Vec<LoadStage>, warmup: Duration) -> Result<Self, String> {
+        if stages.is_empty() {
+            return Err("负载曲线至少需要一个阶段".to_string());
+        }
+        let is_rate = stages[0].target.is_rate();
+        if stages.iter().any(|stage| stage.target.is_rate() != is_rate) {
+            return Err("负载曲线不能混用并发目标和速率目标".to_string());
+        }
+        if stages
+            .iter()
+            .any(|stage| !(stage.target.value().is_finite() && stage.target.value() >= 0.0))
+        {
+            return Err("阶段目标必须是非负数".to_string());
+        }
+
+        let mut boundaries = vec![0.0];
+        let mut cumulative_arrivals = vec![0.0];
+        let mut previous = 0.0;
+        for stage in &stages {
+            let secs = stage.duration_secs as f64;
+            let target = stage.target.value();
+            boundaries.push(boundaries.last().unwrap() + secs);
+            cumulative_arrivals
+                .push(cumulative_arrivals.last().unwrap() + (previous + target) * secs / 2.0);
+            previous = target;
+        }
+
+        Ok(Self {
+            stages,
+            warmup,
+            boundaries,
+            cumulative_arrivals,
+        })
+    }
+
+    pub fn stages(&self) -> &[LoadStage] {
+        &self.stages
+    }
+
+    pub fn warmup(&self) -> Duration {
+        self.warmup
+    }
+
+    pub fn is_rate(&self) -> bool {
+        self.stages[0].target.is_rate()
+    }
+
+    /// 整条曲线的时长
+    pub fn total_duration(&self) -> Duration {
+        Duration::from_secs_f64(*self.boundaries.last().unwrap())
+    }
+
+    /// 阶段 `index` 的开始和结束时刻
+    pub fn stage_window(&self, index: usize) -> (Duration, Duration) {
+        (
+            Duration::from_secs_f64(self.boundaries[index]),
+            Duration::from_secs_f64(self.boundaries[index + 1]),
+        )
+    }
+
+    /// `elapsed` 时刻所在的阶段序号，超出曲线时返回最后一个阶段
+    pub fn stage_index_at(&self, elapsed: Duration) -> usize {
+        let t = elapsed.as_secs_f64();
+        (0..self.stages.len())
+            .find(|&i| t < self.boundaries[i + 1])
+            .unwrap_or(self.stages.len() - 1)
+    }
+
+    /// `elapsed` 时刻的目标值（线性插值）
+    pub fn target_at(&self, elapsed: Duration) -> f64 {
+        let t = elapsed.as_secs_f64();
+        if t >= *self.boundaries.last().unwrap() {
+            return self.stages.last().unwrap().target.value();
+        }
+        let index = self.stage_index_at(elapsed);
+        let (from, to) = self.stage_range(index);
+        let start = self.boundaries[index];
+        let secs = self.boundaries[index + 1] - start;
+        from + (to - from) * (t - start) / secs
+    }
+
+    /// `elapsed` 时刻所处的测试阶段
+    pub fn state_at(&self, elapsed: Duration) -> LoadTestState {
+        if elapsed < self.warmup {
+            LoadTestState::WarmingUp
+        } else if elapsed >= self.total_duration() {
+            LoadTestState::Completed
+        } else {
+            let (from, to) = self.stage_range(self.stage_index_at(elapsed));
+            if to > from {
+                LoadTestState::RampingUp
+            } else {
+                LoadTestState::Running
+            }
+        }
+    }
+
+    /// 开环模式下截至 `elapsed` 应到达的请求数
+    pub fn arrivals_until(&self, elapsed: Duration) -> f64 {
+        let t = elapsed.as_secs_f64();
+        if t >= *self.boundaries.last().unwrap() {
+            return *self.cumulative_arrivals.last().unwrap();
+        }
+        let index = self.stage_index_at(elapsed);
+        let (from, to) = self.stage_range(index);
+        let start = self.boundaries[index];
+        let secs = self.boundaries[index + 1] - start;
+        let dt = t - start;
+        self.cumulative_arrivals[index] + from * dt + (to - from) * dt * dt / (2.0 * secs)
+    }
+
+    /// 开环模式下第 `index` 个请求（从 0 开始）的计划到达时间，超出曲线时返回 `None`
+    pub fn arrival_time(&self, index: u64) -> Option<Duration> {
+        let k = index as f64;
+        let stage = (0..self.stages.len()).find(|&i| k < self.cumulative_arrivals[i + 1])?;
+        let (from, to) = self.stage_range(stage);
+        let secs = self.boundaries[stage + 1] - self.boundaries[stage];
+        let remaining = k - self.cumulative_arrivals[stage];
+
+        // 解 from * dt + (to - from) / (2 * secs) * dt² = remaining
+        let a = (to - from) / (2.0 * secs);
+        let dt = if a.abs() < f64::EPSILON {
+            remaining / from
+        } else {
+            (-from + (from * from + 4.0 * a * remaining).max(0.0).sqrt()) / (2.0 * a)
+        };
+        Some(Duration::from_secs_f64(
+            self.boundaries[stage] + dt.clamp(0.0, secs),
+        ))
+    }
+
+    /// 阶段的起止目标值
+    fn stage_range(&self, index: usize) -> (f64, f64) {
+        let from = if index == 0 {
+            0.0
+        } else {
+            self.stages[index - 1].target.value()
+        };
+        (from, self.stages[index].target.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    /// 10 秒内从 0 升到 10/s，再以 10/s 保持 5 秒
+    fn ramp_then_hold() -> LoadProfile {
+        LoadProfile::new(
+            vec![
+                LoadStage::new(10, StageTarget::Rate(10.0)),
+                LoadStage::new(5, StageTarget::Rate(10.0)),
+            ],
+            Duration::ZERO,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_rates() {
+        assert_eq!(parse_rate("500/s"), Ok(500.0));
+        assert_eq!(parse_rate("6000/m"), Ok(100.0));
+        assert_eq!(parse_rate(" 20 "), Ok(20.0));
+        assert_eq!(parse_rate("5/h").unwrap_err(), "不支持的速率单位: h");
+        assert!(parse_rate("0/s").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+
+    #[test]
+    fn parses_stage_targets() {
+        assert_eq!("100".parse(), Ok(StageTarget::Users(100)));
+        assert_eq!("120/m".parse(), Ok(StageTarget::Rate(2.0)));
+        assert!("-1".parse::<StageTarget>().is_err());
+    }
+
+    #[test]
+    fn rejects_mixed_or_empty_profiles() {
+        assert!(LoadProfile::new(Vec::new(), Duration::ZERO).is_err());
+        let mixed = vec![
+            LoadStage::new(10, StageTarget::Users(10)),
+            LoadStage::new(10, StageTarget::Rate(10.0)),
+        ];
+        assert!(LoadProfile::new(mixed, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn integrates_arrivals_over_ramps() {
+        let profile = ramp_then_hold();
+        assert_eq!(profile.arrivals_until(Duration::ZERO), 0.0);
+        assert!((profile.arrivals_until(secs(5.0)) - 12.5).abs() < 1e-9);
+        assert!((profile.arrivals_until(secs(10.0)) - 50.0).abs() < 1e-9);
+        assert!((profile.arrivals_until(secs(12.0)) - 70.0).abs() < 1e-9);
+        assert!((profile.arrivals_until(secs(60.0)) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn arrival_time_inverts_arrivals_until() {
+        let profile = ramp_then_hold();
+        assert_eq!(profile.arrival_time(0), Some(Duration::ZERO));
+        assert!((profile.arrival_time(12).unwrap().as_secs_f64() - 24f64.sqrt()).abs() < 1e-9);
+        assert!((profile.arrival_time(60).unwrap().as_secs_f64() - 11.0).abs() < 1e-9);
+        assert_eq!(profile.arrival_time(100), None);
+        for index in 0..100 {
+            let at = profile.arrival_time(index).unwrap();
+            assert!((profile.arrivals_until(at) - index as f64).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn reports_targets_and_states() {
+        let profile = LoadProfile::new(
+            vec![
+                LoadStage::new(10, StageTarget::Users(20)),
+                LoadStage::new(10, StageTarget::Users(20)),
+            ],
+            secs(2.0),
+        )
+        .unwrap();
+        assert_eq!(profile.target_at(secs(5.0)), 10.0);
+        assert_eq!(profile.target_at(secs(15.0)), 20.0);
+        assert_eq!(profile.stage_index_at(secs(10.0)), 1);
+        assert_eq!(profile.state_at(secs(1.0)), LoadTestState::WarmingUp);
+        assert_eq!(profile.state_at(secs(5.0)), LoadTestState::RampingUp);
+        assert_eq!(profile.state_at(secs(15.0)), LoadTestState::Running);
+        assert_eq!(profile.state_at(secs(20.0)), LoadTestState::Completed);
+    }
+}
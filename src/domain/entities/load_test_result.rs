@@ -1,15 +1,47 @@
+use super::{
+    ExpectedStatus, ExtractSource, Feeder, LoadProfile, LoadStage, RequestTemplate, RequestTiming,
+    StageTarget, Threshold, TimeSeries,
+};
+use crate::domain::value_objects::{DEFAULT_SIGNIFICANT_FIGURES, LatencyHistogram};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+/// 每类错误最多区分的消息数，之后出现的新消息归入 `OTHER_ERROR_MESSAGE`
+pub const MAX_ERROR_MESSAGES: usize = 20;
+
+/// 超出 `MAX_ERROR_MESSAGES` 后新消息的归类
+pub const OTHER_ERROR_MESSAGE: &str = "(other)";
+
+/// 停止后等待在途请求的默认宽限期（秒）
+pub const DEFAULT_GRACE_PERIOD_SECS: u64 = 10;
+
+/// 压测结果，也是 `json` 导出和 `compare` 读取的格式
+///
+/// 旧版本导出的结果缺少后来新增的字段，缺少的字段取 `Default` 中的值。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LoadTestResult {
     pub total_requests: u64,
     pub successful_requests: u64,
     pub failed_requests: u64,
+    /// 没有发出的请求数（例如模板渲染失败），计入失败请求，但不计入延迟统计
+    pub unsent_requests: u64,
+    pub status_1xx: u64,
     pub status_2xx: u64,
+    pub status_3xx: u64,
     pub status_4xx: u64,
     pub status_5xx: u64,
+    /// 各状态码的响应数，不含没有收到响应的请求
+    pub status_codes: BTreeMap<u16, u64>,
+    /// 收到了响应但状态码不在期望范围内、因而计为失败的请求数
+    pub unexpected_statuses: u64,
+    /// 没有收到响应的请求，按错误类别（`HttpClientError::kind`）统计
+    pub errors: BTreeMap<String, ErrorStats>,
+    /// 所有请求延迟之和，用于计算平均延迟
     pub total_duration: Duration,
+    /// 计入统计的墙钟时间（不含预热），用于计算吞吐和带宽
+    pub elapsed: Duration,
     pub min_latency: Duration,
     pub max_latency: Duration,
     pub avg_latency: Duration,
@@ -17,7 +49,261 @@ pub struct LoadTestResult {
     pub p90_latency: Duration,
     pub p95_latency: Duration,
     pub p99_latency: Duration,
+    pub p999_latency: Duration,
+    pub p9999_latency: Duration,
+    /// 按墙钟时间计算的每秒请求数
     pub qps: f64,
+    /// 请求体字节数，不含请求行和请求头
+    pub bytes_sent: u64,
+    /// 响应体的原始字节数，不含状态行和响应头
+    pub bytes_received: u64,
+    /// 按墙钟时间计算的每秒请求体字节数
+    pub sent_bytes_per_sec: f64,
+    /// 按墙钟时间计算的每秒响应体字节数
+    pub received_bytes_per_sec: f64,
+    /// 从计划发送时间起算的延迟分位（协调遗漏校正后）
+    pub corrected_latency: LatencyPercentiles,
+    /// 开环模式下因在途请求已满而未能按时发出的请求数
+    pub dropped_requests: u64,
+    /// 预热阶段发出、未计入统计的请求数
+    pub warmup_requests: u64,
+    /// 因提取变量失败而提前结束的迭代数
+    pub extraction_failures: u64,
+    pub latency_histogram: LatencyHistogram,
+    pub corrected_histogram: LatencyHistogram,
+    /// 成功收到响应的请求各阶段耗时分布
+    pub phase_timings: PhaseHistograms,
+    /// 多阶段测试中每个阶段各自的结果
+    pub stage_results: Vec<StageResult>,
+    /// 多个请求时每个请求各自的结果
+    pub request_results: Vec<RequestResult>,
+    /// 每个响应检查的通过/失败次数，按请求和检查的声明顺序排列
+    pub checks: Vec<CheckResult>,
+    /// 压测被提前结束时的原因
+    pub aborted: Option<String>,
+    /// 压测被手动停止或取消，结果只包含停止前完成的请求
+    pub interrupted: bool,
+    /// `Unique` 数据源已取完，压测在计划时长结束前停止
+    pub data_exhausted: bool,
+    /// 停止后宽限期内仍未返回、不再等待的在途请求数
+    pub abandoned_requests: u64,
+    /// 按秒分桶的时间序列；只在最终结果中填入，进度快照中为空
+    pub time_series: TimeSeries,
+}
+
+/// 单个负载阶段的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageResult {
+    pub name: String,
+    pub target: StageTarget,
+    pub duration_secs: u64,
+    pub result: LoadTestResult,
+}
+
+/// 单个请求（按名称）的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestResult {
+    pub name: String,
+    /// 按权重混合时该请求的抽取比例
+    pub share: Option<f64>,
+    pub result: LoadTestResult,
+}
+
+/// 同一类请求错误的统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorStats {
+    pub count: u64,
+    /// 按归一化后的错误消息分组
+    pub messages: BTreeMap<String, ErrorMessageStats>,
+}
+
+/// 同一条归一化错误消息的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorMessageStats {
+    pub count: u64,
+    /// 第一次出现时的原始消息
+    pub sample: String,
+}
+
+impl ErrorStats {
+    pub fn record(&mut self, message: &str) {
+        self.count += 1;
+        let mut normalized = normalize_error_message(message);
+        if !self.messages.contains_key(&normalized) && self.messages.len() >= MAX_ERROR_MESSAGES {
+            normalized = OTHER_ERROR_MESSAGE.to_string();
+        }
+        self.messages
+            .entry(normalized)
+            .or_insert_with(|| ErrorMessageStats {
+                count: 0,
+                sample: message.to_string(),
+            })
+            .count += 1;
+    }
+
+    pub fn merge(&mut self, other: &ErrorStats) {
+        self.count += other.count;
+        for (message, theirs) in &other.messages {
+            self.messages
+                .entry(message.clone())
+                .or_insert_with(|| ErrorMessageStats {
+                    count: 0,
+                    sample: theirs.sample.clone(),
+                })
+                .count += theirs.count;
+        }
+    }
+
+    /// 按出现次数从多到少排列的消息
+    pub fn top_messages(&self) -> Vec<(&str, &ErrorMessageStats)> {
+        let mut messages: Vec<_> = self
+            .messages
+            .iter()
+            .map(|(message, stats)| (message.as_str(), stats))
+            .collect();
+        messages.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.count));
+        messages
+    }
+}
+
+/// 把错误消息中的数字（端口、地址、耗时、错误码等）替换为 `N`，相同原因的错误归为一组
+pub fn normalize_error_message(message: &str) -> String {
+    let mut normalized = String::with_capacity(message.len());
+    let mut in_number = false;
+    for c in message.chars() {
+        if c.is_ascii_digit() {
+            if !in_number {
+                normalized.push('N');
+            }
+            in_number = true;
+        } else {
+            normalized.push(c);
+            in_number = false;
+        }
+    }
+    normalized
+}
+
+/// 单个响应检查的统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckResult {
+    /// 检查所属请求的名称
+    pub request: String,
+    pub name: String,
+    pub passed: u64,
+    pub failed: u64,
+}
+
+impl CheckResult {
+    pub fn new(request: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            request: request.into(),
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn record(&mut self, passed: bool) {
+        if passed {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.passed + self.failed
+    }
+
+    /// 通过率，尚无样本时为 1.0
+    pub fn pass_rate(&self) -> f64 {
+        if self.total() == 0 {
+            1.0
+        } else {
+            self.passed as f64 / self.total() as f64
+        }
+    }
+}
+
+/// 一组常用的延迟分位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub p9999: Duration,
+}
+
+impl LatencyPercentiles {
+    pub fn from_histogram(histogram: &LatencyHistogram) -> Self {
+        Self {
+            p50: histogram.value_at_percentile(50.0),
+            p90: histogram.value_at_percentile(90.0),
+            p95: histogram.value_at_percentile(95.0),
+            p99: histogram.value_at_percentile(99.0),
+            p999: histogram.value_at_percentile(99.9),
+            p9999: histogram.value_at_percentile(99.99),
+        }
+    }
+}
+
+/// 请求各阶段（DNS、TCP、TLS、首字节、传输）的耗时直方图
+///
+/// 建连阶段只统计实际新建连接的请求。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhaseHistograms {
+    pub dns: LatencyHistogram,
+    pub connect: LatencyHistogram,
+    pub tls: LatencyHistogram,
+    pub ttfb: LatencyHistogram,
+    pub transfer: LatencyHistogram,
+}
+
+impl PhaseHistograms {
+    pub fn new(significant_figures: u8) -> Self {
+        Self {
+            dns: LatencyHistogram::new(significant_figures),
+            connect: LatencyHistogram::new(significant_figures),
+            tls: LatencyHistogram::new(significant_figures),
+            ttfb: LatencyHistogram::new(significant_figures),
+            transfer: LatencyHistogram::new(significant_figures),
+        }
+    }
+
+    pub fn record(&mut self, timing: &RequestTiming) {
+        if let Some(dns) = timing.dns {
+            self.dns.record(dns);
+        }
+        if let Some(connect) = timing.connect {
+            self.connect.record(connect);
+        }
+        if let Some(tls) = timing.tls {
+            self.tls.record(tls);
+        }
+        self.ttfb.record(timing.ttfb);
+        self.transfer.record(timing.transfer);
+    }
+
+    pub fn merge(&mut self, other: &PhaseHistograms) {
+        self.dns.merge(&other.dns);
+        self.connect.merge(&other.connect);
+        self.tls.merge(&other.tls);
+        self.ttfb.merge(&other.ttfb);
+        self.transfer.merge(&other.transfer);
+    }
+
+    /// 按请求顺序列出各阶段名称及其直方图
+    pub fn phases(&self) -> [(&'static str, &LatencyHistogram); 5] {
+        [
+            ("dns", &self.dns),
+            ("connect", &self.connect),
+            ("tls", &self.tls),
+            ("ttfb", &self.ttfb),
+            ("transfer", &self.transfer),
+        ]
+    }
 }
 
 impl Default for LoadTestResult {
@@ -26,10 +312,17 @@ impl Default for LoadTestResult {
             total_requests: 0,
             successful_requests: 0,
             failed_requests: 0,
+            unsent_requests: 0,
+            status_1xx: 0,
             status_2xx: 0,
+            status_3xx: 0,
             status_4xx: 0,
             status_5xx: 0,
+            status_codes: BTreeMap::new(),
+            unexpected_statuses: 0,
+            errors: BTreeMap::new(),
             total_duration: Duration::ZERO,
+            elapsed: Duration::ZERO,
             min_latency: Duration::MAX,
             max_latency: Duration::ZERO,
             avg_latency: Duration::ZERO,
@@ -37,27 +330,66 @@ impl Default for LoadTestResult {
             p90_latency: Duration::ZERO,
             p95_latency: Duration::ZERO,
             p99_latency: Duration::ZERO,
+            p999_latency: Duration::ZERO,
+            p9999_latency: Duration::ZERO,
             qps: 0.0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            sent_bytes_per_sec: 0.0,
+            received_bytes_per_sec: 0.0,
+            corrected_latency: LatencyPercentiles::default(),
+            dropped_requests: 0,
+            warmup_requests: 0,
+            extraction_failures: 0,
+            latency_histogram: LatencyHistogram::default(),
+            corrected_histogram: LatencyHistogram::default(),
+            phase_timings: PhaseHistograms::default(),
+            stage_results: Vec::new(),
+            request_results: Vec::new(),
+            checks: Vec::new(),
+            aborted: None,
+            interrupted: false,
+            data_exhausted: false,
+            abandoned_requests: 0,
+            time_series: TimeSeries::new(),
         }
     }
 }
 
 impl LoadTestResult {
-    pub fn add_response(&mut self, status: u16, duration: Duration) {
+    /// 使用指定的直方图精度（有效数字位数）创建结果
+    pub fn with_latency_precision(significant_figures: u8) -> Self {
+        Self {
+            latency_histogram: LatencyHistogram::new(significant_figures),
+            corrected_histogram: LatencyHistogram::new(significant_figures),
+            phase_timings: PhaseHistograms::new(significant_figures),
+            ..Self::default()
+        }
+    }
+
+    /// 记录一个请求，`status` 为 0 表示没有收到响应
+    ///
+    /// 成功与否由调用方按期望状态码、检查和请求错误判定，`successful_requests` 与
+    /// `failed_requests` 之和始终等于 `total_requests`。
+    pub fn add_response(&mut self, status: u16, duration: Duration, success: bool) {
         self.total_requests += 1;
         self.total_duration += duration;
 
-        if status < 200 {
-            self.failed_requests += 1;
-        } else if status < 300 {
+        if success {
             self.successful_requests += 1;
-            self.status_2xx += 1;
-        } else if status < 400 {
-            self.status_2xx += 1;
-        } else if status < 500 {
-            self.status_4xx += 1;
         } else {
-            self.status_5xx += 1;
+            self.failed_requests += 1;
+        }
+
+        if status > 0 {
+            *self.status_codes.entry(status).or_default() += 1;
+            match status / 100 {
+                1 => self.status_1xx += 1,
+                2 => self.status_2xx += 1,
+                3 => self.status_3xx += 1,
+                4 => self.status_4xx += 1,
+                _ => self.status_5xx += 1,
+            }
         }
 
         if duration < self.min_latency {
@@ -66,16 +398,228 @@ impl LoadTestResult {
         if duration > self.max_latency {
             self.max_latency = duration;
         }
+
+        self.latency_histogram.record(duration);
+    }
+
+    /// 记录一个没有发出的失败请求，只计数和记录错误，不影响延迟统计
+    pub fn add_unsent_failure(&mut self, kind: &str, message: &str) {
+        self.total_requests += 1;
+        self.failed_requests += 1;
+        self.unsent_requests += 1;
+        self.add_error(kind, message);
+    }
+
+    /// 实际发出、计入延迟统计的请求数
+    pub fn sent_requests(&self) -> u64 {
+        self.total_requests - self.unsent_requests
+    }
+
+    /// 记录一个没有收到响应的请求的错误类别和消息，请求本身仍由 `add_response` 计数
+    pub fn add_error(&mut self, kind: &str, message: &str) {
+        self.errors
+            .entry(kind.to_string())
+            .or_default()
+            .record(message);
+    }
+
+    /// 记录协调遗漏校正后的延迟
+    ///
+    /// 不传 `expected_interval` 时 `latency` 为从计划发送时间起算的延迟；传入时 `latency`
+    /// 为实际延迟，按该间隔补齐被遗漏的样本，两种校正只取其一。
+    pub fn add_corrected_latency(
+        &mut self,
+        latency: Duration,
+        expected_interval: Option<Duration>,
+    ) {
+        match expected_interval {
+            Some(interval) => self.corrected_histogram.record_corrected(latency, interval),
+            None => self.corrected_histogram.record(latency),
+        }
     }
 
+    /// 从直方图刷新各分位延迟
+    pub fn calculate_percentiles(&mut self) {
+        let percentiles = LatencyPercentiles::from_histogram(&self.latency_histogram);
+        self.p50_latency = percentiles.p50;
+        self.p90_latency = percentiles.p90;
+        self.p95_latency = percentiles.p95;
+        self.p99_latency = percentiles.p99;
+        self.p999_latency = percentiles.p999;
+        self.p9999_latency = percentiles.p9999;
+        self.corrected_latency = LatencyPercentiles::from_histogram(&self.corrected_histogram);
+    }
+
+    /// 任意分位延迟，`percentile` 取值 0.0..=100.0
+    ///
+    /// 旧版本导出的结果没有直方图，此时取不低于 `percentile` 的已存分位（更早的版本
+    /// 没有导出的高分位为零，跳过），都没有时取最大延迟。
+    pub fn latency_at_percentile(&self, percentile: f64) -> Duration {
+        if !self.latency_histogram.is_empty() {
+            return self.latency_histogram.value_at_percentile(percentile);
+        }
+        [
+            (50.0, self.p50_latency),
+            (90.0, self.p90_latency),
+            (95.0, self.p95_latency),
+            (99.0, self.p99_latency),
+            (99.9, self.p999_latency),
+            (99.99, self.p9999_latency),
+        ]
+        .into_iter()
+        .find(|&(stored, latency)| stored >= percentile && !latency.is_zero())
+        .map_or(self.max_latency, |(_, latency)| latency)
+    }
+
+    /// 合并另一份结果（例如不同阶段或不同节点的结果）
+    ///
+    /// 两份结果视为同时进行，墙钟时间取较长的一方；合并先后进行的结果后需要重新设置 `elapsed`。
+    pub fn merge(&mut self, other: &LoadTestResult) {
+        self.total_requests += other.total_requests;
+        self.successful_requests += other.successful_requests;
+        self.failed_requests += other.failed_requests;
+        self.unsent_requests += other.unsent_requests;
+        self.status_1xx += other.status_1xx;
+        self.status_2xx += other.status_2xx;
+        self.status_3xx += other.status_3xx;
+        self.status_4xx += other.status_4xx;
+        self.status_5xx += other.status_5xx;
+        for (status, count) in &other.status_codes {
+            *self.status_codes.entry(*status).or_default() += count;
+        }
+        self.unexpected_statuses += other.unexpected_statuses;
+        for (kind, stats) in &other.errors {
+            self.errors.entry(kind.clone()).or_default().merge(stats);
+        }
+        self.total_duration += other.total_duration;
+        self.elapsed = self.elapsed.max(other.elapsed);
+        self.bytes_sent += other.bytes_sent;
+        self.bytes_received += other.bytes_received;
+        self.min_latency = self.min_latency.min(other.min_latency);
+        self.max_latency = self.max_latency.max(other.max_latency);
+        self.dropped_requests += other.dropped_requests;
+        self.warmup_requests += other.warmup_requests;
+        self.extraction_failures += other.extraction_failures;
+        self.latency_histogram.merge(&other.latency_histogram);
+        self.corrected_histogram.merge(&other.corrected_histogram);
+        self.phase_timings.merge(&other.phase_timings);
+        if self.aborted.is_none() {
+            self.aborted = other.aborted.clone();
+        }
+        self.interrupted |= other.interrupted;
+        self.data_exhausted |= other.data_exhausted;
+        self.abandoned_requests += other.abandoned_requests;
+        self.time_series.merge(&other.time_series);
+        for check in &other.checks {
+            match self
+                .checks
+                .iter_mut()
+                .find(|c| c.request == check.request && c.name == check.name)
+            {
+                Some(existing) => {
+                    existing.passed += check.passed;
+                    existing.failed += check.failed;
+                }
+                None => self.checks.push(check.clone()),
+            }
+        }
+
+        self.calculate_qps();
+        self.calculate_percentiles();
+    }
+
+    /// 按 `elapsed` 刷新吞吐和带宽，并刷新平均延迟
     pub fn calculate_qps(&mut self) {
-        if self.total_duration.as_secs_f64() > 0.0 {
-            self.qps = self.total_requests as f64 / self.total_duration.as_secs_f64();
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.qps = self.total_requests as f64 / secs;
+            self.sent_bytes_per_sec = self.bytes_sent as f64 / secs;
+            self.received_bytes_per_sec = self.bytes_received as f64 / secs;
+        }
+        if self.sent_requests() > 0 {
+            self.avg_latency = self.total_duration / self.sent_requests() as u32;
+        }
+    }
+
+    /// 平均每个请求发送的字节数
+    pub fn avg_bytes_sent(&self) -> f64 {
+        if self.total_requests == 0 {
+            0.0
+        } else {
+            self.bytes_sent as f64 / self.total_requests as f64
         }
-        if self.total_requests > 0 {
-            self.avg_latency = self.total_duration / self.total_requests as u32;
+    }
+
+    /// 平均每个请求接收的字节数
+    pub fn avg_bytes_received(&self) -> f64 {
+        if self.total_requests == 0 {
+            0.0
+        } else {
+            self.bytes_received as f64 / self.total_requests as f64
         }
     }
+
+    /// 供进度回调使用的汇总快照
+    pub fn progress(&self) -> LoadTestProgress {
+        LoadTestProgress {
+            total_requests: self.total_requests,
+            successful_requests: self.successful_requests,
+            failed_requests: self.failed_requests,
+            unsent_requests: self.unsent_requests,
+            status_codes: self.status_codes.clone(),
+            unexpected_statuses: self.unexpected_statuses,
+            errors: self
+                .errors
+                .iter()
+                .map(|(kind, stats)| (kind.clone(), stats.count))
+                .collect(),
+            checks: self.checks.clone(),
+            extraction_failures: self.extraction_failures,
+            dropped_requests: self.dropped_requests,
+            total_duration: self.total_duration,
+            elapsed: self.elapsed,
+            qps: self.qps,
+            sent_bytes_per_sec: self.sent_bytes_per_sec,
+            received_bytes_per_sec: self.received_bytes_per_sec,
+            avg_latency: self.avg_latency,
+            max_latency: self.max_latency,
+            p50_latency: self.p50_latency,
+            p90_latency: self.p90_latency,
+            p95_latency: self.p95_latency,
+            p99_latency: self.p99_latency,
+            p999_latency: self.p999_latency,
+        }
+    }
+}
+
+/// 压测过程中的进度快照
+///
+/// 只有汇总数值，不含直方图、时间序列以及分阶段和分请求的结果，每次刷新进度时复制的开销很小。
+#[derive(Debug, Clone, Default)]
+pub struct LoadTestProgress {
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub unsent_requests: u64,
+    pub status_codes: BTreeMap<u16, u64>,
+    pub unexpected_statuses: u64,
+    /// 没有收到响应的请求数，按错误类别统计
+    pub errors: BTreeMap<String, u64>,
+    pub checks: Vec<CheckResult>,
+    pub extraction_failures: u64,
+    pub dropped_requests: u64,
+    pub total_duration: Duration,
+    pub elapsed: Duration,
+    pub qps: f64,
+    pub sent_bytes_per_sec: f64,
+    pub received_bytes_per_sec: f64,
+    pub avg_latency: Duration,
+    pub max_latency: Duration,
+    pub p50_latency: Duration,
+    pub p90_latency: Duration,
+    pub p95_latency: Duration,
+    pub p99_latency: Duration,
+    pub p999_latency: Duration,
 }
 
 #[derive(Debug, Clone)]
@@ -83,9 +627,33 @@ pub struct LoadTestConfig {
     pub url: String,
     pub method: String,
     pub concurrent_users: usize,
+    /// 计入统计的测试时长（不含预热）
     pub duration_secs: u64,
+    /// 预热时长：从开始起这段时间内计划发出的请求不计入统计
     pub warmup_secs: u64,
+    /// 爬坡时长：从开始起在这段时间内线性启动用户（开环模式下线性提升速率）
     pub ramp_up_secs: u64,
+    /// 多阶段负载曲线；非空时取代 `concurrent_users`、`arrival_rate`、
+    /// `duration_secs` 和 `ramp_up_secs`，预热时长计入曲线开头
+    pub stages: Vec<LoadStage>,
+    /// 开环模式的目标到达速率（请求/秒），`None` 表示闭环模式
+    pub arrival_rate: Option<f64>,
+    /// 开环模式下允许的最大在途请求数
+    pub max_in_flight: usize,
+    /// 闭环模式下按发送间隔补齐协调遗漏的样本
+    pub coordinated_omission_backfill: bool,
+    /// 延迟直方图的有效数字位数（1-4）
+    pub latency_precision: u8,
+    /// 每次迭代依次发送的请求；非空时取代 `url` 和 `method`
+    pub requests: Vec<RequestTemplate>,
+    /// 未配置 `requests` 时计为成功的状态码，为空时 2xx 和 3xx 计为成功
+    pub expected_statuses: Vec<ExpectedStatus>,
+    /// 每次迭代开始前为虚拟用户取一行数据，各列写入变量表
+    pub feeders: Vec<Feeder>,
+    /// 判定压测是否通过的阈值
+    pub thresholds: Vec<Threshold>,
+    /// 停止或中止后等待在途请求完成的最长时间，超过后不再等待
+    pub grace_period_secs: u64,
 }
 
 impl Default for LoadTestConfig {
@@ -97,16 +665,78 @@ impl Default for LoadTestConfig {
             duration_secs: 30,
             warmup_secs: 5,
             ramp_up_secs: 10,
+            stages: Vec::new(),
+            arrival_rate: None,
+            max_in_flight: 1000,
+            coordinated_omission_backfill: false,
+            latency_precision: DEFAULT_SIGNIFICANT_FIGURES,
+            requests: Vec::new(),
+            expected_statuses: Vec::new(),
+            feeders: Vec::new(),
+            thresholds: Vec::new(),
+            grace_period_secs: DEFAULT_GRACE_PERIOD_SECS,
         }
     }
 }
 
+impl LoadTestConfig {
+    pub fn warmup(&self) -> Duration {
+        Duration::from_secs(self.warmup_secs)
+    }
+
+    pub fn ramp_up(&self) -> Duration {
+        Duration::from_secs(self.ramp_up_secs)
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+
+    /// 是否有请求从 `Set-Cookie` 提取变量
+    ///
+    /// 客户端跟随重定向时只能看到最终响应的响应头，这类压测应使用不跟随重定向的客户端。
+    pub fn extracts_cookies(&self) -> bool {
+        self.requests.iter().any(|template| {
+            template
+                .extract
+                .iter()
+                .any(|extractor| matches!(extractor.source, ExtractSource::Cookie(_)))
+        })
+    }
+
+    /// 构建负载曲线
+    ///
+    /// 未配置阶段时，等价于「爬坡到目标 + 保持到预热和测试时长结束」两个阶段。
+    pub fn load_profile(&self) -> Result<LoadProfile, String> {
+        if !self.stages.is_empty() {
+            return LoadProfile::new(self.stages.clone(), self.warmup());
+        }
+
+        let target = match self.arrival_rate {
+            Some(rate) => StageTarget::Rate(rate),
+            None => StageTarget::Users(self.concurrent_users),
+        };
+        let total_secs = self.warmup_secs + self.duration_secs;
+        LoadProfile::new(
+            vec![
+                LoadStage::new(self.ramp_up_secs.min(total_secs), target),
+                LoadStage::new(total_secs.saturating_sub(self.ramp_up_secs), target),
+            ],
+            self.warmup(),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoadTestState {
     NotStarted,
     WarmingUp,
     RampingUp,
     Running,
+    /// 手动暂停，不开始新的迭代
+    Paused,
     Completed,
+    /// 被手动停止或取消，结果只包含停止前完成的请求
+    Interrupted,
     Failed,
 }
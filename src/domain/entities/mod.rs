@@ -1,5 +1,35 @@
+pub mod check;
+pub mod comparison;
+pub mod expected_status;
+pub mod extraction;
+pub mod feeder;
 pub mod http_request;
+pub mod load_profile;
 pub mod load_test_result;
+pub mod request_record;
+pub mod request_template;
+pub mod scenario;
+pub mod threshold;
+pub mod time_series;
 
-pub use http_request::{HttpRequest, HttpResponse};
-pub use load_test_result::{LoadTestConfig, LoadTestResult, LoadTestState};
+pub use check::{Check, CheckRule, JsonSchema, run_checks};
+pub use comparison::{
+    DEFAULT_COMPARISON_METRICS, MetricComparison, Tolerance, compare_results,
+};
+pub use expected_status::{ExpectedStatus, is_expected_status};
+pub use extraction::{ExtractSource, Extractor, extract_variables};
+pub use feeder::{FeedStrategy, Feeder, FeederSpec};
+pub use http_request::{HttpRequest, HttpResponse, RequestTiming};
+pub use load_profile::{LoadProfile, LoadStage, StageTarget, parse_rate};
+pub use load_test_result::{
+    CheckResult, DEFAULT_GRACE_PERIOD_SECS, ErrorMessageStats, ErrorStats, LatencyPercentiles,
+    LoadTestConfig, LoadTestProgress, LoadTestResult, LoadTestState, MAX_ERROR_MESSAGES,
+    OTHER_ERROR_MESSAGE, PhaseHistograms, RequestResult, StageResult, normalize_error_message,
+};
+pub use request_record::RequestRecord;
+pub use request_template::{RequestMix, RequestTemplate, check_variables};
+pub use scenario::{Scenario, ScenarioLoad, ScenarioRequest};
+pub use threshold::{
+    Comparison, RemainingLoad, Threshold, ThresholdMetric, ThresholdVerdict, evaluate_thresholds,
+};
+pub use time_series::{TIME_SERIES_SIGNIFICANT_FIGURES, TimeBucket, TimeSeries};
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 单个请求的原始记录，用于逐请求导出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestRecord {
+    /// 实际发送时间
+    pub timestamp: DateTime<Utc>,
+    /// 请求名称
+    pub request: String,
+    /// 状态码，请求失败时为 0
+    pub status: u16,
+    /// 从实际发送到收到响应的耗时
+    #[serde(rename = "latency_ms", with = "duration_ms")]
+    pub latency: Duration,
+    /// 响应体字节数
+    pub bytes: u64,
+    /// 请求体字节数
+    #[serde(default)]
+    pub bytes_sent: u64,
+    /// 失败类别：客户端错误的类别，或响应检查失败时为 `check`
+    pub error: Option<String>,
+    /// 是否为预热阶段的请求（未计入统计）
+    pub warmup: bool,
+}
+
+/// 以浮点毫秒序列化耗时，便于表格和笔记本直接使用
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let millis = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(millis / 1000.0).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,245 @@
+use super::{Check, ExpectedStatus, Extractor, HttpRequest, is_expected_status};
+use rand::{Rng, RngExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 压测中按名称区分的一个请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    pub name: String,
+    /// 可包含 `{{name}}` 占位符，发送前用虚拟用户的变量替换
+    pub request: HttpRequest,
+    /// 收到响应后执行的提取规则
+    #[serde(default)]
+    pub extract: Vec<Extractor>,
+    /// 收到响应后执行的检查，任一失败时请求计为失败
+    #[serde(default)]
+    pub checks: Vec<Check>,
+    /// 计为成功的状态码，为空时 2xx 和 3xx 计为成功
+    #[serde(default)]
+    pub expected_statuses: Vec<ExpectedStatus>,
+    /// 按权重混合时的相对权重；设置后每次迭代只按权重抽取一个请求
+    #[serde(default)]
+    pub weight: Option<u32>,
+}
+
+impl RequestTemplate {
+    pub fn new(name: impl Into<String>, request: HttpRequest) -> Self {
+        Self {
+            name: name.into(),
+            request,
+            extract: Vec::new(),
+            checks: Vec::new(),
+            expected_statuses: Vec::new(),
+            weight: None,
+        }
+    }
+
+    pub fn with_extract(mut self, extract: Vec<Extractor>) -> Self {
+        self.extract = extract;
+        self
+    }
+
+    pub fn with_checks(mut self, checks: Vec<Check>) -> Self {
+        self.checks = checks;
+        self
+    }
+
+    pub fn with_expected_statuses(mut self, expected_statuses: Vec<ExpectedStatus>) -> Self {
+        self.expected_statuses = expected_statuses;
+        self
+    }
+
+    /// 收到该状态码的响应是否计为成功
+    pub fn expects_status(&self, status: u16) -> bool {
+        is_expected_status(&self.expected_statuses, status)
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+}
+
+/// 按权重抽取请求的分布
+#[derive(Debug, Clone)]
+pub struct RequestMix {
+    /// 各请求权重的前缀和
+    cumulative: Vec<u64>,
+}
+
+impl RequestMix {
+    /// 所有请求都未设置权重时返回 `None`，表示按顺序执行全部请求
+    pub fn new(templates: &[RequestTemplate]) -> Result<Option<Self>, String> {
+        if templates.iter().all(|t| t.weight.is_none()) {
+            return Ok(None);
+        }
+
+        let mut total = 0u64;
+        let mut cumulative = Vec::with_capacity(templates.len());
+        for template in templates {
+            match template.weight {
+                Some(0) => return Err(format!("请求 {} 的权重必须大于 0", template.name)),
+                Some(weight) => total += u64::from(weight),
+                None => {
+                    return Err(format!(
+                        "请求 {} 未设置权重：权重必须为所有请求设置或都不设置",
+                        template.name
+                    ));
+                }
+            }
+            cumulative.push(total);
+        }
+        Ok(Some(Self { cumulative }))
+    }
+
+    /// 请求 `index` 在混合中所占的比例
+    pub fn share(&self, index: usize) -> f64 {
+        let previous = index.checked_sub(1).map_or(0, |i| self.cumulative[i]);
+        (self.cumulative[index] - previous) as f64 / self.total() as f64
+    }
+
+    /// 按权重随机抽取一个请求的下标
+    pub fn sample(&self) -> usize {
+        self.sample_with(&mut rand::rng())
+    }
+
+    /// 用给定的随机数生成器按权重抽取，固定种子时抽取结果可复现
+    pub fn sample_with<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let point = rng.random_range(0..self.total());
+        self.cumulative.partition_point(|&bound| bound <= point)
+    }
+
+    fn total(&self) -> u64 {
+        self.cumulative.last().copied().unwrap_or(0)
+    }
+}
+
+/// 检查每个请求引用的变量都已定义：来自 `predefined`（如数据源的列）或前面请求的提取规则
+///
+/// 按权重混合时请求之间没有先后顺序，只能引用 `predefined` 中的变量。
+pub fn check_variables<'a>(
+    templates: &'a [RequestTemplate],
+    predefined: impl IntoIterator<Item = &'a str>,
+) -> Result<(), String> {
+    let sequential = templates.iter().all(|t| t.weight.is_none());
+    let mut defined: HashSet<&str> = predefined.into_iter().collect();
+    for template in templates {
+        if let Some(undefined) = template
+            .request
+            .placeholders()
+            .find(|name| !defined.contains(name))
+        {
+            return Err(format!(
+                "请求 {} 引用了未定义的变量: {}",
+                template.name, undefined
+            ));
+        }
+        if sequential {
+            defined.extend(template.extract.iter().map(|e| e.variable.as_str()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::ExtractSource;
+    use crate::domain::value_objects::Url;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn template(name: &str, url: &str, weight: Option<u32>) -> RequestTemplate {
+        let request = HttpRequest {
+            url: Url::parse(url).unwrap(),
+            ..HttpRequest::default()
+        };
+        RequestTemplate {
+            weight,
+            ..RequestTemplate::new(name, request)
+        }
+    }
+
+    #[test]
+    fn samples_requests_in_proportion_to_weight() {
+        let templates = [
+            template("a", "http://test/a", Some(1)),
+            template("b", "http://test/b", Some(3)),
+            template("c", "http://test/c", Some(6)),
+        ];
+        let mix = RequestMix::new(&templates).unwrap().unwrap();
+        assert_eq!(mix.share(0), 0.1);
+        assert_eq!(mix.share(1), 0.3);
+        assert_eq!(mix.share(2), 0.6);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let draws = 100_000;
+        let mut counts = [0usize; 3];
+        for _ in 0..draws {
+            counts[mix.sample_with(&mut rng)] += 1;
+        }
+        for (index, count) in counts.iter().enumerate() {
+            let observed = *count as f64 / draws as f64;
+            assert!(
+                (observed - mix.share(index)).abs() < 0.01,
+                "{index}: {observed}"
+            );
+        }
+
+        let mut first = StdRng::seed_from_u64(42);
+        let mut second = StdRng::seed_from_u64(42);
+        let a: Vec<usize> = (0..100).map(|_| mix.sample_with(&mut first)).collect();
+        let b: Vec<usize> = (0..100).map(|_| mix.sample_with(&mut second)).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn rejects_zero_and_partial_weights() {
+        assert!(RequestMix::new(&[]).unwrap().is_none());
+        let unweighted = [template("a", "http://test/a", None)];
+        assert!(RequestMix::new(&unweighted).unwrap().is_none());
+
+        let zero = [
+            template("a", "http://test/a", Some(1)),
+            template("b", "http://test/b", Some(0)),
+        ];
+        assert!(RequestMix::new(&zero).unwrap_err().contains("b"));
+
+        let partial = [
+            template("a", "http://test/a", Some(1)),
+            template("b", "http://test/b", None),
+        ];
+        assert!(
+            RequestMix::new(&partial)
+                .unwrap_err()
+                .contains("未设置权重")
+        );
+    }
+
+    #[test]
+    fn checks_variables_in_request_order() {
+        let login =
+            template("login", "http://test/login", None).with_extract(vec![Extractor::new(
+                "token",
+                ExtractSource::Header("X-Token".to_string()),
+            )]);
+        let profile = template("profile", "http://test/users/{{id}}?t={{token}}", None);
+
+        assert!(check_variables(&[login.clone(), profile.clone()], ["id"]).is_ok());
+
+        let error = check_variables(&[profile.clone(), login.clone()], ["id"]).unwrap_err();
+        assert!(
+            error.contains("profile") && error.contains("token"),
+            "{error}"
+        );
+
+        let error = check_variables(&[login.clone(), profile.clone()], []).unwrap_err();
+        assert!(error.contains("id"), "{error}");
+
+        // 按权重混合时不能引用其他请求提取的变量
+        let weighted = [login.with_weight(1), profile.with_weight(1)];
+        let error = check_variables(&weighted, ["id"]).unwrap_err();
+        assert!(error.contains("token"), "{error}");
+    }
+}
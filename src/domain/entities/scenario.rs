@@ -0,0 +1,385 @@
+//! 压测场景
+//!
+//! 场景文件（YAML / TOML / JSON）完整描述一次压测：目标服务、请求、
+//! 负载曲线和阈值，可以随服务代码一起版本化。
+//!
+//! 请求按顺序组成一次迭代：数据源的列和前面请求 `extract` 出的变量可以在
+//! 后续请求的 URL、请求头和请求体中以 `{{name}}` 引用。
+//!
+//! 为请求设置 `weight` 后改为按权重混合：每次迭代只抽取一个请求发送，
+//! 用来模拟多个接口按比例混合的真实流量。
+
+use super::expected_status::deserialize_one_or_many;
+use super::{
+    Check, DEFAULT_GRACE_PERIOD_SECS, ExpectedStatus, ExtractSource, Extractor, Feeder, FeederSpec,
+    HttpRequest, LoadStage, LoadTestConfig, RequestMix, RequestTemplate, Threshold,
+    check_variables,
+};
+use crate::domain::value_objects::{
+    Body, DEFAULT_SIGNIFICANT_FIGURES, Headers, MAX_SIGNIFICANT_FIGURES, Url,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// 场景中请求的默认超时
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 请求路径的公共前缀，例如 `https://api.example.com/v1`
+    pub base_url: String,
+    /// 所有请求共用的请求头，可被单个请求覆盖
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub requests: Vec<ScenarioRequest>,
+    /// 为虚拟用户提供参数的数据文件
+    #[serde(default)]
+    pub feeders: Vec<FeederSpec>,
+    #[serde(default)]
+    pub load: ScenarioLoad,
+    #[serde(default)]
+    pub thresholds: Vec<Threshold>,
+}
+
+/// 场景中的单个请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// 相对 `base_url` 的路径，也可以是完整 URL
+    pub path: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 原样发送的文本请求体
+    #[serde(default)]
+    pub body: Option<String>,
+    /// JSON 请求体，与 `body` 互斥
+    #[serde(default)]
+    pub json: Option<serde_json::Value>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// 变量名到取值来源的映射
+    #[serde(default)]
+    pub extract: BTreeMap<String, ExtractSource>,
+    /// 对响应的检查，任一失败时请求计为失败
+    #[serde(default)]
+    pub checks: Vec<Check>,
+    /// 计为成功的状态码，可以是单个值或列表，例如 `302` 或 `[200, "3xx"]`；
+    /// 未设置时 2xx 和 3xx 计为成功
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub expect_status: Vec<ExpectedStatus>,
+    /// 按权重混合时的相对权重，必须为所有请求设置或都不设置
+    #[serde(default)]
+    pub weight: Option<u32>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// 场景的负载设置，字段含义与 `LoadTestConfig` 相同
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioLoad {
+    pub concurrent_users: usize,
+    pub duration_secs: u64,
+    pub warmup_secs: u64,
+    pub ramp_up_secs: u64,
+    pub stages: Vec<LoadStage>,
+    pub arrival_rate: Option<f64>,
+    pub max_in_flight: usize,
+    pub coordinated_omission_backfill: bool,
+    pub latency_precision: u8,
+    pub grace_period_secs: u64,
+}
+
+impl Default for ScenarioLoad {
+    fn default() -> Self {
+        Self {
+            concurrent_users: 10,
+            duration_secs: 30,
+            warmup_secs: 0,
+            ramp_up_secs: 0,
+            stages: Vec::new(),
+            arrival_rate: None,
+            max_in_flight: 1000,
+            coordinated_omission_backfill: false,
+            latency_precision: DEFAULT_SIGNIFICANT_FIGURES,
+            grace_period_secs: DEFAULT_GRACE_PERIOD_SECS,
+        }
+    }
+}
+
+impl Scenario {
+    /// 场景显示名称，未命名时使用 `base_url`
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.base_url)
+    }
+
+    /// 构建场景中的全部请求
+    pub fn request_templates(&self) -> Result<Vec<RequestTemplate>, String> {
+        if self.requests.is_empty() {
+            return Err("场景至少需要一个请求".to_string());
+        }
+
+        self.requests
+            .iter()
+            .map(|spec| {
+                let request = self.build_request(spec)?;
+                let name = spec
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("{} {}", request.method, spec.path));
+                let extract = spec
+                    .extract
+                    .iter()
+                    .map(|(variable, source)| Extractor::new(variable.as_str(), source.clone()))
+                    .collect();
+                let template = RequestTemplate::new(name, request)
+                    .with_extract(extract)
+                    .with_checks(spec.checks.clone())
+                    .with_expected_statuses(spec.expect_status.clone());
+                Ok(match spec.weight {
+                    Some(weight) => template.with_weight(weight),
+                    None => template,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .and_then(|templates| {
+                RequestMix::new(&templates)?;
+                Ok(templates)
+            })
+    }
+
+    /// 用已加载的数据源（与 `feeders` 一一对应）转换为压测配置，并检查请求引用的变量
+    pub fn load_test_config(&self, feeders: Vec<Feeder>) -> Result<LoadTestConfig, String> {
+        let load = &self.load;
+        if !(1..=MAX_SIGNIFICANT_FIGURES).contains(&load.latency_precision) {
+            return Err(format!(
+                "latency_precision 必须在 1 到 {} 之间",
+                MAX_SIGNIFICANT_FIGURES
+            ));
+        }
+        let requests = self.request_templates()?;
+        check_variables(
+            &requests,
+            feeders
+                .iter()
+                .flat_map(|feeder| feeder.columns().iter().map(String::as_str)),
+        )?;
+        let first = &requests[0].request;
+
+        Ok(LoadTestConfig {
+            url: first.url.to_string(),
+            method: first.method.to_string(),
+            concurrent_users: load.concurrent_users,
+            duration_secs: load.duration_secs,
+            warmup_secs: load.warmup_secs,
+            ramp_up_secs: load.ramp_up_secs,
+            stages: load.stages.clone(),
+            arrival_rate: load.arrival_rate,
+            max_in_flight: load.max_in_flight,
+            coordinated_omission_backfill: load.coordinated_omission_backfill,
+            latency_precision: load.latency_precision,
+            requests,
+            expected_statuses: Vec::new(),
+            feeders,
+            thresholds: self.thresholds.clone(),
+            grace_period_secs: load.grace_period_secs,
+        })
+    }
+
+    fn build_request(&self, spec: &ScenarioRequest) -> Result<HttpRequest, String> {
+        let url = if spec.path.starts_with("http://") || spec.path.starts_with("https://") {
+            spec.path.clone()
+        } else {
+            format!(
+                "{}/{}",
+                self.base_url.trim_end_matches('/'),
+                spec.path.trim_start_matches('/')
+            )
+        };
+
+        // 请求头名称不区分大小写，请求中的 `Content-Type` 覆盖公共的 `content-type`
+        let mut headers = Headers::from(self.headers.clone());
+        for (key, value) in &spec.headers {
+            headers.insert_ignore_case(key.as_str(), value.as_str());
+        }
+
+        let body = match (&spec.body, &spec.json) {
+            (Some(_), Some(_)) => {
+                return Err(format!("请求 {} 不能同时设置 body 和 json", spec.path));
+            }
+            (Some(text), None) => Some(Body::text(text.as_str())),
+            (None, Some(json)) => {
+                if headers.get_ignore_case("content-type").is_none() {
+                    headers.insert("Content-Type", "application/json");
+                }
+                Some(Body::json(json.to_string()))
+            }
+            (None, None) => None,
+        };
+
+        Ok(HttpRequest {
+            id: uuid::Uuid::new_v4(),
+            url: Url::parse(&url).map_err(|e| format!("{}: {}", url, e))?,
+            method: spec.method.parse()?,
+            headers,
+            body,
+            timeout: Duration::from_secs(spec.timeout_secs.unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{FeedStrategy, StageTarget};
+
+    fn scenario(value: serde_json::Value) -> Scenario {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn headers(request: &HttpRequest) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        headers.sort();
+        headers
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn request_headers_override_shared_headers_ignoring_case() {
+        let scenario = scenario(serde_json::json!({
+            "base_url": "https://api.test/v1/",
+            "headers": { "content-type": "text/plain", "x-team": "bolt" },
+            "requests": [
+                { "path": "/upload", "method": "POST", "body": "a,b",
+                  "headers": { "Content-Type": "text/csv", "X-Trace": "1" } },
+                { "path": "orders", "method": "POST", "json": { "id": 1 } },
+                { "path": "https://other.test/health" }
+            ]
+        }));
+        let templates = scenario.request_templates().unwrap();
+
+        let upload = &templates[0].request;
+        assert_eq!(upload.url.as_str(), "https://api.test/v1/upload");
+        assert_eq!(
+            headers(upload),
+            [
+                pair("Content-Type", "text/csv"),
+                pair("X-Trace", "1"),
+                pair("x-team", "bolt")
+            ]
+        );
+
+        // 公共请求头已设置内容类型时，JSON 请求体不再添加
+        let orders = &templates[1].request;
+        assert_eq!(
+            headers(orders),
+            [pair("content-type", "text/plain"), pair("x-team", "bolt")]
+        );
+        assert_eq!(templates[1].name, "POST orders");
+        assert_eq!(
+            templates[2].request.url.as_str(),
+            "https://other.test/health"
+        );
+    }
+
+    #[test]
+    fn json_bodies_default_to_a_json_content_type() {
+        let scenario = scenario(serde_json::json!({
+            "base_url": "https://api.test",
+            "requests": [{ "path": "orders", "method": "POST", "json": { "id": 1 } }]
+        }));
+        let request = &scenario.request_templates().unwrap()[0].request;
+        assert_eq!(headers(request), [pair("Content-Type", "application/json")]);
+        assert_eq!(request.body.as_ref().unwrap().as_str(), Some(r#"{"id":1}"#));
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let error = |value: serde_json::Value| scenario(value).request_templates().unwrap_err();
+        assert_eq!(
+            error(serde_json::json!({ "base_url": "https://api.test", "requests": [] })),
+            "场景至少需要一个请求"
+        );
+        assert!(
+            error(serde_json::json!({
+                "base_url": "https://api.test",
+                "requests": [{ "path": "a", "body": "x", "json": {} }]
+            }))
+            .contains("不能同时设置 body 和 json")
+        );
+        assert!(
+            error(serde_json::json!({
+                "base_url": "https://api.test",
+                "requests": [{ "path": "a", "weight": 1 }, { "path": "b" }]
+            }))
+            .contains("未设置权重")
+        );
+    }
+
+    #[test]
+    fn builds_a_load_test_config() {
+        let scenario = scenario(serde_json::json!({
+            "base_url": "https://api.test",
+            "requests": [
+                { "name": "login", "method": "POST", "path": "login",
+                  "extract": { "token": { "json": "$.token" } } },
+                { "path": "users/{{id}}", "headers": { "Authorization": "Bearer {{token}}" },
+                  "timeout_secs": 5, "expect_status": [200, 404] }
+            ],
+            "load": {
+                "arrival_rate": 50.0, "duration_secs": 60, "warmup_secs": 5,
+                "latency_precision": 2
+            },
+            "thresholds": ["p99 < 300ms"]
+        }));
+        let feeder = Feeder::new(
+            "users",
+            vec![[("id".to_string(), "1".to_string())].into()],
+            FeedStrategy::Unique,
+        )
+        .unwrap();
+
+        let config = scenario.load_test_config(vec![feeder]).unwrap();
+        assert_eq!(config.url, "https://api.test/login");
+        assert_eq!(config.method, "POST");
+        assert_eq!(config.arrival_rate, Some(50.0));
+        assert_eq!((config.duration_secs, config.warmup_secs), (60, 5));
+        assert_eq!(config.concurrent_users, 10);
+        assert_eq!(config.latency_precision, 2);
+        assert_eq!(config.requests.len(), 2);
+        assert_eq!(config.requests[1].request.timeout, Duration::from_secs(5));
+        assert_eq!(config.requests[1].expected_statuses.len(), 2);
+        assert_eq!(config.thresholds.len(), 1);
+        assert_eq!(config.feeders.len(), 1);
+        assert!(matches!(
+            config.load_profile().unwrap().stages()[1].target,
+            StageTarget::Rate(_)
+        ));
+
+        // 没有数据源时 `id` 未定义
+        let error = scenario.load_test_config(Vec::new()).unwrap_err();
+        assert!(error.contains("id"), "{error}");
+
+        let mut imprecise = scenario.clone();
+        imprecise.load.latency_precision = 5;
+        let error = imprecise.load_test_config(Vec::new()).unwrap_err();
+        assert!(error.contains("latency_precision"), "{error}");
+    }
+}
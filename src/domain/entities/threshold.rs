@@ -0,0 +1,503 @@
+//! 阈值
+//!
+//! 阈值用 `指标 运算符 值` 的表达式描述，例如 `p99 < 300ms`、
+//! `error_rate < 1%`、`qps > 500`。压测结束后对最终结果逐条判定；
+//! 设置了 `abort_on_fail` 的阈值在压测过程中一旦可以证明无法再满足就提前结束压测。
+
+use super::LoadTestResult;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 阈值约束的指标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdMetric {
+    /// 延迟分位，取值 0.0..=100.0
+    Percentile(f64),
+    AvgLatency,
+    MinLatency,
+    MaxLatency,
+    /// 失败请求占比
+    ErrorRate,
+    /// 成功请求占比
+    SuccessRate,
+    Qps,
+}
+
+impl ThresholdMetric {
+    /// 是否为延迟类指标，其阈值以毫秒为单位
+    pub fn is_latency(&self) -> bool {
+        matches!(
+            self,
+            Self::Percentile(_) | Self::AvgLatency | Self::MinLatency | Self::MaxLatency
+        )
+    }
+
+    /// 是否为比例类指标，其阈值取值 0.0..=1.0
+    pub fn is_ratio(&self) -> bool {
+        matches!(self, Self::ErrorRate | Self::SuccessRate)
+    }
+
+    /// 取值越大越好的指标
+    pub fn higher_is_better(&self) -> bool {
+        matches!(self, Self::Qps | Self::SuccessRate)
+    }
+
+    /// 从结果中取出该指标的值，延迟以毫秒为单位；没有请求时为 `None`
+    pub fn observe(&self, result: &LoadTestResult) -> Option<f64> {
+        if result.total_requests == 0 {
+            return None;
+        }
+        let millis = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        let total = result.total_requests as f64;
+        Some(match self {
+            Self::Percentile(p) => millis(result.latency_at_percentile(*p)),
+            Self::AvgLatency => millis(result.avg_latency),
+            Self::MinLatency => millis(result.min_latency),
+            Self::MaxLatency => millis(result.max_latency),
+            Self::ErrorRate => result.failed_requests as f64 / total,
+            Self::SuccessRate => result.successful_requests as f64 / total,
+            Self::Qps => result.qps,
+        })
+    }
+
+    /// 按指标类型格式化一个取值
+    pub fn format_value(&self, value: f64) -> String {
+        if self.is_latency() {
+            format!("{:.2}ms", value)
+        } else if self.is_ratio() {
+            format!("{:.2}%", value * 100.0)
+        } else {
+            format!("{:.1}", value)
+        }
+    }
+}
+
+impl FromStr for ThresholdMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "avg" | "mean" => Ok(Self::AvgLatency),
+            "min" => Ok(Self::MinLatency),
+            "max" => Ok(Self::MaxLatency),
+            "error_rate" => Ok(Self::ErrorRate),
+            "success_rate" => Ok(Self::SuccessRate),
+            "qps" | "rps" => Ok(Self::Qps),
+            metric => parse_percentile(metric)
+                .map(Self::Percentile)
+                .ok_or_else(|| format!("Invalid threshold metric: {}", s)),
+        }
+    }
+}
+
+/// 解析 `p99`、`p99.9`、`p999`（即 99.9）、`p9999`（即 99.99）这样的分位写法
+///
+/// 只有以 `99` 开头、没有小数点的多位数字按省略小数点处理，其余按字面数值解析。
+fn parse_percentile(s: &str) -> Option<f64> {
+    let digits = s.strip_prefix('p')?;
+    let digits = digits
+        .strip_prefix('(')
+        .and_then(|d| d.strip_suffix(')'))
+        .unwrap_or(digits);
+    let percentile = match digits.strip_prefix("99") {
+        Some(rest)
+            if !rest.is_empty()
+                && !digits.contains('.')
+                && rest.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            format!("99.{}", rest).parse().ok()?
+        }
+        _ => digits.parse::<f64>().ok()?,
+    };
+    (0.0..=100.0).contains(&percentile).then_some(percentile)
+}
+
+impl fmt::Display for ThresholdMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percentile(p) => write!(f, "p{}", p),
+            Self::AvgLatency => write!(f, "avg"),
+            Self::MinLatency => write!(f, "min"),
+            Self::MaxLatency => write!(f, "max"),
+            Self::ErrorRate => write!(f, "error_rate"),
+            Self::SuccessRate => write!(f, "success_rate"),
+            Self::Qps => write!(f, "qps"),
+        }
+    }
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    pub fn holds(&self, observed: f64, expected: f64) -> bool {
+        match self {
+            Self::Lt => observed < expected,
+            Self::Le => observed <= expected,
+            Self::Gt => observed > expected,
+            Self::Ge => observed >= expected,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lt => write!(f, "<"),
+            Self::Le => write!(f, "<="),
+            Self::Gt => write!(f, ">"),
+            Self::Ge => write!(f, ">="),
+        }
+    }
+}
+
+/// 压测剩余部分的上界，用来证明阈值已不可能满足
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RemainingLoad {
+    /// 之后最多还会计入统计的请求数；闭环模式无法预知时为 `None`
+    pub max_requests: Option<u64>,
+    /// 计入统计的计划总时长（不含预热）
+    pub planned_elapsed: Duration,
+}
+
+/// 对压测结果中某个指标的约束
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ThresholdRepr", into = "ThresholdRepr")]
+pub struct Threshold {
+    pub metric: ThresholdMetric,
+    pub comparison: Comparison,
+    /// 延迟类指标以毫秒为单位，比例类指标取值 0.0..=1.0
+    pub value: f64,
+    /// 压测过程中确定无法满足时提前结束压测
+    pub abort_on_fail: bool,
+}
+
+/// 场景文件中的写法：表达式字符串，或 `{ threshold: "p99 < 300ms", abort_on_fail: true }`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ThresholdRepr {
+    Expr(String),
+    Full {
+        threshold: String,
+        #[serde(default)]
+        abort_on_fail: bool,
+    },
+}
+
+impl Threshold {
+    /// 从结果中取出该指标的观测值，单位与 `value` 相同；尚无请求时为 `None`
+    pub fn observe(&self, result: &LoadTestResult) -> Option<f64> {
+        self.metric.observe(result)
+    }
+
+    /// 判定最终结果，没有请求时视为不通过
+    pub fn evaluate(&self, result: &LoadTestResult) -> ThresholdVerdict {
+        let observed = self.observe(result);
+        ThresholdVerdict {
+            threshold: self.clone(),
+            observed,
+            passed: observed.is_some_and(|observed| self.comparison.holds(observed, self.value)),
+        }
+    }
+
+    /// 压测尚未结束时判断阈值是否已可以证明无法满足
+    ///
+    /// 最大延迟的上限和最小延迟的下限一旦越过就不可能回来；其余指标假设剩余的
+    /// `remaining.max_requests` 个请求全部取最有利的结果，仍不满足时才算无法满足。
+    /// 剩余请求数未知时只判断最大、最小延迟。
+    pub fn is_unrecoverable(&self, result: &LoadTestResult, remaining: &RemainingLoad) -> bool {
+        let Some(observed) = self.observe(result) else {
+            return false;
+        };
+        if self.comparison.holds(observed, self.value) {
+            return false;
+        }
+        let upper_bound = matches!(self.comparison, Comparison::Lt | Comparison::Le);
+        match self.metric {
+            ThresholdMetric::MaxLatency if upper_bound => true,
+            ThresholdMetric::MinLatency if !upper_bound => true,
+            _ => remaining
+                .max_requests
+                .is_some_and(|max_requests| !self.can_still_pass(result, max_requests, remaining)),
+        }
+    }
+
+    /// 剩余 `max_requests` 个请求全部取最有利的结果时，最终结果是否可能满足阈值
+    fn can_still_pass(
+        &self,
+        result: &LoadTestResult,
+        max_requests: u64,
+        remaining: &RemainingLoad,
+    ) -> bool {
+        let upper_bound = matches!(self.comparison, Comparison::Lt | Comparison::Le);
+        let total = result.total_requests + max_requests;
+        let best = match self.metric {
+            ThresholdMetric::ErrorRate => {
+                let failed = result.failed_requests + if upper_bound { 0 } else { max_requests };
+                failed as f64 / total as f64
+            }
+            ThresholdMetric::SuccessRate => {
+                let successful =
+                    result.successful_requests + if upper_bound { 0 } else { max_requests };
+                successful as f64 / total as f64
+            }
+            // 剩余请求的延迟最低为 0，没有上限；没有发出的请求不计入延迟
+            ThresholdMetric::AvgLatency if upper_bound => {
+                let sent = result.sent_requests() + max_requests;
+                result.total_duration.as_secs_f64() * 1000.0 / sent.max(1) as f64
+            }
+            // 请求数只增不减，最终吞吐不超过全部请求在计划时长内完成的速率
+            ThresholdMetric::Qps if !upper_bound && !remaining.planned_elapsed.is_zero() => {
+                total as f64 / remaining.planned_elapsed.as_secs_f64()
+            }
+            ThresholdMetric::Percentile(p) => {
+                let histogram = &result.latency_histogram;
+                let value = Duration::from_secs_f64(self.value / 1000.0);
+                let sent = result.sent_requests() + max_requests;
+                let rank = ((p / 100.0 * sent as f64).ceil() as u64).max(1);
+                // 分位所在的样本确定落在阈值不利一侧时无法满足
+                return if upper_bound {
+                    histogram.len().saturating_sub(histogram.count_above(value)) + max_requests
+                        >= rank
+                } else {
+                    histogram.count_below(value) < rank
+                };
+            }
+            _ => return true,
+        };
+        self.comparison.holds(best, self.value)
+    }
+
+    /// 按指标类型格式化一个取值
+    pub fn format_value(&self, value: f64) -> String {
+        self.metric.format_value(value)
+    }
+}
+
+/// 单个阈值的判定结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdVerdict {
+    pub threshold: Threshold,
+    /// 观测值，单位与阈值相同；没有请求时为 `None`
+    pub observed: Option<f64>,
+    pub passed: bool,
+}
+
+/// 对最终结果逐条判定阈值
+pub fn evaluate_thresholds(
+    thresholds: &[Threshold],
+    result: &LoadTestResult,
+) -> Vec<ThresholdVerdict> {
+    thresholds
+        .iter()
+        .map(|threshold| threshold.evaluate(result))
+        .collect()
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, op) = s
+            .find(['<', '>'])
+            .map(|i| (i, &s[i..]))
+            .ok_or_else(|| format!("Invalid threshold (missing operator): {}", s))?;
+        let (comparison, rest) = if let Some(rest) = op.strip_prefix("<=") {
+            (Comparison::Le, rest)
+        } else if let Some(rest) = op.strip_prefix(">=") {
+            (Comparison::Ge, rest)
+        } else if let Some(rest) = op.strip_prefix('<') {
+            (Comparison::Lt, rest)
+        } else {
+            (Comparison::Gt, &op[1..])
+        };
+
+        let metric: ThresholdMetric = s[..index].trim().parse()?;
+        let value = parse_value(metric, rest.trim())
+            .ok_or_else(|| format!("Invalid threshold value: {}", s))?;
+
+        Ok(Self {
+            metric,
+            comparison,
+            value,
+            abort_on_fail: false,
+        })
+    }
+}
+
+/// 按指标类型解析阈值：延迟支持 us/ms/s 单位（默认毫秒），比例支持百分号
+fn parse_value(metric: ThresholdMetric, s: &str) -> Option<f64> {
+    let value = if metric.is_latency() {
+        if let Some(us) = s.strip_suffix("us").or_else(|| s.strip_suffix("µs")) {
+            us.trim().parse::<f64>().ok()? / 1000.0
+        } else if let Some(ms) = s.strip_suffix("ms") {
+            ms.trim().parse().ok()?
+        } else if let Some(secs) = s.strip_suffix('s') {
+            secs.trim().parse::<f64>().ok()? * 1000.0
+        } else {
+            s.parse().ok()?
+        }
+    } else if let Some(percent) = s.strip_suffix('%').filter(|_| metric.is_ratio()) {
+        percent.trim().parse::<f64>().ok()? / 100.0
+    } else {
+        s.parse().ok()?
+    };
+    (value.is_finite() && value >= 0.0).then_some(value)
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.metric, self.comparison)?;
+        if self.metric.is_latency() {
+            write!(f, "{}ms", self.value)
+        } else if self.metric.is_ratio() {
+            write!(f, "{}%", self.value * 100.0)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+impl TryFrom<ThresholdRepr> for Threshold {
+    type Error = String;
+
+    fn try_from(repr: ThresholdRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ThresholdRepr::Expr(expr) => expr.parse(),
+            ThresholdRepr::Full {
+                threshold,
+                abort_on_fail,
+            } => Ok(Self {
+                abort_on_fail,
+                ..threshold.parse()?
+            }),
+        }
+    }
+}
+
+impl From<Threshold> for ThresholdRepr {
+    fn from(threshold: Threshold) -> Self {
+        if threshold.abort_on_fail {
+            Self::Full {
+                threshold: threshold.to_string(),
+                abort_on_fail: true,
+            }
+        } else {
+            Self::Expr(threshold.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_with(latencies_ms: &[u64], failed: usize) -> LoadTestResult {
+        let mut result = LoadTestResult::default();
+        for (i, &ms) in latencies_ms.iter().enumerate() {
+            result.add_response(200, Duration::from_millis(ms), i >= failed);
+        }
+        result.calculate_qps();
+        result.calculate_percentiles();
+        result
+    }
+
+    fn remaining(max_requests: Option<u64>) -> RemainingLoad {
+        RemainingLoad {
+            max_requests,
+            planned_elapsed: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn parses_percentile_shorthand() {
+        let percentile = |s: &str| match s.parse::<ThresholdMetric>() {
+            Ok(ThresholdMetric::Percentile(p)) => Some(p),
+            _ => None,
+        };
+        assert_eq!(percentile("p50"), Some(50.0));
+        assert_eq!(percentile("p99"), Some(99.0));
+        assert_eq!(percentile("p99.9"), Some(99.9));
+        assert_eq!(percentile("p999"), Some(99.9));
+        assert_eq!(percentile("p9999"), Some(99.99));
+        assert_eq!(percentile("p(95)"), Some(95.0));
+        assert_eq!(percentile("p100"), Some(100.0));
+        assert_eq!(percentile("p1000"), None);
+        assert_eq!(percentile("p99x"), None);
+    }
+
+    #[test]
+    fn parses_threshold_expressions() {
+        let threshold: Threshold = "p99 < 1.5s".parse().unwrap();
+        assert_eq!(threshold.metric, ThresholdMetric::Percentile(99.0));
+        assert_eq!(threshold.comparison, Comparison::Lt);
+        assert_eq!(threshold.value, 1500.0);
+
+        let threshold: Threshold = "error_rate <= 1%".parse().unwrap();
+        assert_eq!(threshold.metric, ThresholdMetric::ErrorRate);
+        assert_eq!(threshold.comparison, Comparison::Le);
+        assert!((threshold.value - 0.01).abs() < 1e-12);
+
+        let threshold: Threshold = "qps >= 500".parse().unwrap();
+        assert_eq!(threshold.comparison, Comparison::Ge);
+        assert_eq!(threshold.value, 500.0);
+
+        assert!("p99 300ms".parse::<Threshold>().is_err());
+        assert!("p99 < fast".parse::<Threshold>().is_err());
+        assert!("latency < 300ms".parse::<Threshold>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for expr in ["p99.9 < 300ms", "error_rate < 1%", "qps > 500"] {
+            let threshold: Threshold = expr.parse().unwrap();
+            assert_eq!(
+                threshold.to_string().parse::<Threshold>().unwrap(),
+                threshold
+            );
+        }
+    }
+
+    #[test]
+    fn max_latency_violation_is_unrecoverable() {
+        let threshold: Threshold = "max < 100ms".parse().unwrap();
+        let result = result_with(&[10, 200], 0);
+        assert!(threshold.is_unrecoverable(&result, &remaining(None)));
+    }
+
+    #[test]
+    fn early_error_burst_is_recoverable_while_requests_remain() {
+        let threshold: Threshold = "error_rate < 1%".parse().unwrap();
+        let result = result_with(&[10; 100], 5);
+        assert!(!threshold.is_unrecoverable(&result, &remaining(None)));
+        assert!(!threshold.is_unrecoverable(&result, &remaining(Some(10_000))));
+        assert!(threshold.is_unrecoverable(&result, &remaining(Some(100))));
+    }
+
+    #[test]
+    fn low_qps_during_ramp_up_is_recoverable() {
+        let threshold: Threshold = "qps > 500".parse().unwrap();
+        let mut result = result_with(&[10; 100], 0);
+        result.elapsed = Duration::from_secs(1);
+        result.calculate_qps();
+        assert!(!threshold.is_unrecoverable(&result, &remaining(None)));
+        assert!(!threshold.is_unrecoverable(&result, &remaining(Some(5_000))));
+        assert!(threshold.is_unrecoverable(&result, &remaining(Some(1_000))));
+    }
+
+    #[test]
+    fn percentile_is_unrecoverable_only_when_slow_samples_dominate() {
+        let threshold: Threshold = "p90 < 100ms".parse().unwrap();
+        let result = result_with(&[500; 50], 0);
+        assert!(!threshold.is_unrecoverable(&result, &remaining(Some(1_000))));
+        assert!(threshold.is_unrecoverable(&result, &remaining(Some(100))));
+    }
+}
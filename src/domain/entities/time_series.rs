@@ -0,0 +1,200 @@
+//! 按秒分桶的时间序列
+//!
+//! 累计结果看不出压测过程中的变化，时间序列把每一秒完成的请求单独统计，
+//! 用于报告中的吞吐、错误率和延迟曲线。
+
+use crate::domain::value_objects::LatencyHistogram;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 每秒延迟直方图的有效数字位数
+///
+/// 每个桶都有一个直方图，精度低一些可以让长时间压测的内存占用保持在可接受范围。
+pub const TIME_SERIES_SIGNIFICANT_FIGURES: u8 = 2;
+
+/// 一秒内完成的请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBucket {
+    /// 桶起点相对测试开始的秒数
+    pub second: u64,
+    pub requests: u64,
+    /// 失败请求数，口径与 `LoadTestResult::failed_requests` 相同
+    pub errors: u64,
+    /// 按错误类别统计的失败请求数
+    #[serde(default)]
+    pub errors_by_kind: BTreeMap<String, u64>,
+    /// 响应体字节数
+    #[serde(default)]
+    pub bytes: u64,
+    pub latency: LatencyHistogram,
+}
+
+impl TimeBucket {
+    pub fn new(second: u64) -> Self {
+        Self {
+            second,
+            requests: 0,
+            errors: 0,
+            errors_by_kind: BTreeMap::new(),
+            bytes: 0,
+            latency: LatencyHistogram::new(TIME_SERIES_SIGNIFICANT_FIGURES),
+        }
+    }
+
+    /// 失败请求占比，桶内没有请求时为 0
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+}
+
+/// 按请求完成时间分桶的时间序列，桶连续排列，没有请求的秒也保留空桶
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeSeries {
+    pub buckets: Vec<TimeBucket>,
+}
+
+impl TimeSeries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个在测试开始后 `completed_at` 完成的请求，失败请求带上错误类别
+    ///
+    /// 没有发出的请求没有延迟，`latency` 为 `None`，只计入请求数和错误数。
+    pub fn record(
+        &mut self,
+        completed_at: Duration,
+        latency: Option<Duration>,
+        bytes: u64,
+        error: Option<&str>,
+    ) {
+        let bucket = self.bucket_mut(completed_at.as_secs());
+        bucket.requests += 1;
+        bucket.bytes += bytes;
+        if let Some(kind) = error {
+            bucket.errors += 1;
+            *bucket.errors_by_kind.entry(kind.to_string()).or_default() += 1;
+        }
+        if let Some(latency) = latency {
+            bucket.latency.record(latency);
+        }
+    }
+
+    /// 按秒合并另一条时间序列
+    pub fn merge(&mut self, other: &TimeSeries) {
+        for theirs in &other.buckets {
+            let bucket = self.bucket_mut(theirs.second);
+            bucket.requests += theirs.requests;
+            bucket.errors += theirs.errors;
+            for (kind, count) in &theirs.errors_by_kind {
+                *bucket.errors_by_kind.entry(kind.clone()).or_default() += count;
+            }
+            bucket.bytes += theirs.bytes;
+            bucket.latency.merge(&theirs.latency);
+        }
+    }
+
+    /// 取第 `second` 秒的桶，必要时补齐中间的空桶
+    fn bucket_mut(&mut self, second: u64) -> &mut TimeBucket {
+        let first = self.buckets.first().map_or(second, |bucket| bucket.second);
+        if second < first {
+            // 结果汇总有少量乱序，早于第一个桶的请求补到开头
+            self.buckets
+                .splice(0..0, (second..first).map(TimeBucket::new));
+        }
+
+        let first = first.min(second);
+        let index = (second - first) as usize;
+        while self.buckets.len() <= index {
+            let next = first + self.buckets.len() as u64;
+            self.buckets.push(TimeBucket::new(next));
+        }
+        &mut self.buckets[index]
+    }
+
+    /// 出现过的所有错误类别，按名称排序
+    pub fn error_kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self
+            .buckets
+            .iter()
+            .flat_map(|bucket| bucket.errors_by_kind.keys().map(String::as_str))
+            .collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        kinds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn seconds(series: &TimeSeries) -> Vec<u64> {
+        series.buckets.iter().map(|bucket| bucket.second).collect()
+    }
+
+    #[test]
+    fn buckets_by_whole_seconds_of_completion_time() {
+        let mut series = TimeSeries::new();
+        assert!(series.is_empty());
+        series.record(ms(0), Some(ms(5)), 10, None);
+        series.record(ms(999), Some(ms(7)), 10, None);
+        series.record(ms(1000), Some(ms(9)), 10, Some("timeout"));
+        series.record(ms(3500), None, 0, Some("template"));
+
+        assert_eq!(seconds(&series), [0, 1, 2, 3]);
+        let [first, second, empty, last] = &series.buckets[..] else {
+            unreachable!()
+        };
+        assert_eq!((first.requests, first.errors, first.bytes), (2, 0, 20));
+        assert_eq!(first.latency.len(), 2);
+        assert_eq!((second.requests, second.errors), (1, 1));
+        assert_eq!(second.errors_by_kind["timeout"], 1);
+        assert_eq!(second.error_rate(), 1.0);
+        assert_eq!((empty.requests, empty.error_rate()), (0, 0.0));
+        // 没有发出的请求只计入请求数和错误数
+        assert_eq!((last.requests, last.errors), (1, 1));
+        assert!(last.latency.is_empty());
+        assert_eq!(series.error_kinds(), ["template", "timeout"]);
+    }
+
+    #[test]
+    fn fills_gaps_before_the_first_bucket_for_out_of_order_samples() {
+        let mut series = TimeSeries::new();
+        series.record(ms(5200), Some(ms(1)), 0, None);
+        series.record(ms(2100), Some(ms(1)), 0, None);
+        assert_eq!(seconds(&series), [2, 3, 4, 5]);
+        assert_eq!(series.buckets[0].requests, 1);
+        assert_eq!(series.buckets[3].requests, 1);
+    }
+
+    #[test]
+    fn merges_buckets_by_second() {
+        let mut a = TimeSeries::new();
+        a.record(ms(1500), Some(ms(10)), 5, Some("connection"));
+        let mut b = TimeSeries::new();
+        b.record(ms(200), Some(ms(20)), 7, None);
+        b.record(ms(1700), Some(ms(30)), 7, Some("connection"));
+
+        a.merge(&b);
+        assert_eq!(seconds(&a), [0, 1]);
+        assert_eq!((a.buckets[0].requests, a.buckets[0].bytes), (1, 7));
+        let merged = &a.buckets[1];
+        assert_eq!((merged.requests, merged.errors, merged.bytes), (2, 2, 12));
+        assert_eq!(merged.errors_by_kind["connection"], 2);
+        assert_eq!(merged.latency.len(), 2);
+    }
+}
@@ -13,6 +13,8 @@ pub struct RequestOptions {
     pub timeout_seconds: u64,
     /// 是否验证SSL证书
     pub verify_ssl: bool,
+    /// 是否复用连接；关闭后每个请求都新建连接，用于测量握手开销
+    pub reuse_connections: bool,
 }
 
 impl Default for RequestOptions {
@@ -22,6 +24,7 @@ impl Default for RequestOptions {
             redirect_limit: 10,
             timeout_seconds: 30,
             verify_ssl: true,
+            reuse_connections: true,
         }
     }
 }
@@ -34,6 +37,7 @@ impl RequestOptions {
             redirect_limit: 0,
             timeout_seconds: 60,
             verify_ssl: true,
+            reuse_connections: true,
         }
     }
     
@@ -44,6 +48,7 @@ impl RequestOptions {
             redirect_limit: 20,
             timeout_seconds: 10,
             verify_ssl: false,
+            reuse_connections: true,
         }
     }
 }
\ No newline at end of file
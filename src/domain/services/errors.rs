@@ -1,3 +1,4 @@
+use std::error::Error as StdError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -11,6 +12,9 @@ pub enum HttpClientError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
     #[error("SSL error: {0}")]
     SslError(String),
 
@@ -30,6 +34,24 @@ pub enum HttpClientError {
     Unknown(String),
 }
 
+impl HttpClientError {
+    /// 错误类别的稳定名称，用于结果导出和统计
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ConnectionFailed(_) => "connection",
+            Self::Timeout => "timeout",
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::InvalidConfig(_) => "invalid_config",
+            Self::SslError(_) => "tls",
+            Self::TooManyRedirects => "too_many_redirects",
+            Self::Cancelled => "cancelled",
+            Self::IoError(_) => "io",
+            Self::SerializationError(_) => "serialization",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+}
+
 impl From<reqwest::Error> for HttpClientError {
     fn from(e: reqwest::Error) -> Self {
         if e.is_timeout() {
@@ -48,6 +70,71 @@ impl From<reqwest::Error> for HttpClientError {
 
 impl From<hyper::Error> for HttpClientError {
     fn from(e: hyper::Error) -> Self {
-        Self::ConnectionFailed(e.to_string())
+        Self::from_hyper(&e, e.to_string())
+    }
+}
+
+impl From<hyper_util::client::legacy::Error> for HttpClientError {
+    /// 连接池错误本身只说明出错的阶段，按错误链中的底层错误分类
+    fn from(e: hyper_util::client::legacy::Error) -> Self {
+        let message = error_chain(&e);
+        if find_source::<native_tls::Error>(&e).is_some() {
+            Self::SslError(message)
+        } else if find_source::<std::io::Error>(&e)
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::TimedOut)
+        {
+            Self::Timeout
+        } else if e.is_connect() {
+            Self::ConnectionFailed(message)
+        } else if let Some(hyper_error) = find_source::<hyper::Error>(&e) {
+            Self::from_hyper(hyper_error, message)
+        } else {
+            Self::Unknown(message)
+        }
+    }
+}
+
+impl HttpClientError {
+    /// 与 reqwest 的分类一致：超时、无法解析的响应，其余视为连接问题
+    fn from_hyper(e: &hyper::Error, message: String) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else if e.is_parse() || e.is_parse_status() || e.is_parse_too_large() {
+            Self::SerializationError(message)
+        } else {
+            Self::ConnectionFailed(message)
+        }
+    }
+}
+
+/// 在错误链中查找指定类型的错误，包括包装在 `io::Error` 中的错误
+fn find_source<'a, T: StdError + 'static>(e: &'a (dyn StdError + 'static)) -> Option<&'a T> {
+    let mut current = Some(e);
+    while let Some(error) = current {
+        if let Some(found) = error.downcast_ref::<T>() {
+            return Some(found);
+        }
+        // `io::Error::source` 跳过了被包装的错误本身
+        if let Some(found) = error
+            .downcast_ref::<std::io::Error>()
+            .and_then(|io| io.get_ref())
+            .and_then(|inner| inner.downcast_ref::<T>())
+        {
+            return Some(found);
+        }
+        current = error.source();
+    }
+    None
+}
+
+/// 错误及其全部来源，以 `: ` 连接
+fn error_chain(e: &(dyn StdError + 'static)) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
     }
+    message
 }
@@ -16,4 +16,18 @@ pub trait HttpClient: Send + Sync {
     
     /// 关闭客户端连接
     async fn close(&self) -> Result<(), super::HttpClientError>;
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl<T: HttpClient + ?Sized> HttpClient for Box<T> {
+    async fn execute(
+        &self,
+        request: &HttpRequest,
+    ) -> Result<crate::domain::entities::HttpResponse, super::HttpClientError> {
+        (**self).execute(request).await
+    }
+
+    async fn close(&self) -> Result<(), super::HttpClientError> {
+        (**self).close().await
+    }
+}
@@ -5,7 +5,9 @@
 mod errors;
 mod http_client;
 mod dto;
+mod request_log;
 
 pub use errors::HttpClientError;
 pub use http_client::HttpClient;
-pub use dto::RequestOptions;
\ No newline at end of file
+pub use dto::RequestOptions;
+pub use request_log::RequestLog;
\ No newline at end of file
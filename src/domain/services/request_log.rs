@@ -0,0 +1,13 @@
+//! 逐请求记录服务接口
+
+use crate::domain::entities::RequestRecord;
+
+/// 逐请求记录的接收端
+///
+/// `record` 在压测的结果汇总循环中调用，实现不能阻塞，写入应交给后台完成。
+pub trait RequestLog: Send + Sync {
+    fn record(&self, record: RequestRecord);
+
+    /// 压测结束时调用，等待已提交的记录全部写完
+    fn finish(&self) -> Result<(), String>;
+}
@@ -1,8 +1,18 @@
-use serde::{Deserialize, Serialize};
+use super::template::{self, Escape, Variables};
+use bytes::Bytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
 
+/// 请求体或响应体
+///
+/// 内容以原始字节保存：发送时直接交给客户端，响应体只在需要时才按 UTF-8 解码。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Body {
-    content: String,
+    #[serde(
+        serialize_with = "serialize_text",
+        deserialize_with = "deserialize_text"
+    )]
+    content: Bytes,
     content_type: ContentType,
 }
 
@@ -19,7 +29,7 @@ pub enum ContentType {
 impl Default for Body {
     fn default() -> Self {
         Self {
-            content: String::new(),
+            content: Bytes::new(),
             content_type: ContentType::Text,
         }
     }
@@ -28,27 +38,46 @@ impl Default for Body {
 impl Body {
     pub fn json(content: impl Into<String>) -> Self {
         Self {
-            content: content.into(),
+            content: Bytes::from(content.into()),
             content_type: ContentType::Json,
         }
     }
 
     pub fn text(content: impl Into<String>) -> Self {
         Self {
-            content: content.into(),
+            content: Bytes::from(content.into()),
             content_type: ContentType::Text,
         }
     }
 
     pub fn from_string(content: impl Into<String>, content_type: ContentType) -> Self {
         Self {
-            content: content.into(),
+            content: Bytes::from(content.into()),
             content_type,
         }
     }
 
-    pub fn content(&self) -> &str {
-        &self.content
+    /// 原始字节，不按文本解码
+    pub fn from_bytes(content: Bytes, content_type: ContentType) -> Self {
+        Self {
+            content,
+            content_type,
+        }
+    }
+
+    /// 按 UTF-8 解码的内容，无效的字节替换为 U+FFFD；内容本身有效时不复制
+    pub fn content(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.content)
+    }
+
+    /// 内容是有效的 UTF-8 时返回文本
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.content).ok()
+    }
+
+    /// 原始字节，克隆只增加引用计数
+    pub fn bytes(&self) -> Bytes {
+        self.content.clone()
     }
 
     pub fn content_type(&self) -> ContentType {
@@ -62,12 +91,61 @@ impl Body {
     pub fn len(&self) -> usize {
         self.content.len()
     }
+
+    /// 替换内容中的 `{{name}}` 占位符
+    ///
+    /// JSON 请求体中的值按 JSON 字符串转义，表单中的值做百分号编码，其余类型原样插入。
+    pub fn render(&self, variables: &Variables) -> Result<Self, String> {
+        let escape = match self.content_type {
+            ContentType::Json => Escape::Json,
+            ContentType::FormUrlEncoded => Escape::Url,
+            _ => Escape::None,
+        };
+        Ok(Self {
+            content: Bytes::from(
+                template::render(&self.content(), variables, escape)?.into_owned(),
+            ),
+            content_type: self.content_type,
+        })
+    }
 }
 
 use std::fmt;
 
 impl fmt::Display for Body {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.content)
+        write!(f, "{}", self.content())
+    }
+}
+
+/// 序列化为文本，与内容为字符串时的格式相同
+fn serialize_text<S: Serializer>(content: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&String::from_utf8_lossy(content))
+}
+
+fn deserialize_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+    String::deserialize(deserializer).map(Bytes::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_raw_bytes_lossily() {
+        let body = Body::from_bytes(Bytes::from_static(b"ok \xff"), ContentType::Text);
+        assert_eq!(body.len(), 4);
+        assert_eq!(body.content(), "ok \u{fffd}");
+        assert_eq!(body.as_str(), None);
+        assert!(matches!(Body::text("ok").content(), Cow::Borrowed("ok")));
+    }
+
+    #[test]
+    fn serializes_as_text() {
+        let body = Body::json(r#"{"a":1}"#);
+        let json = serde_json::to_string(&body).unwrap();
+        assert_eq!(json, r#"{"content":"{\"a\":1}","content_type":"Json"}"#);
+        let restored: Body = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.bytes(), body.bytes());
     }
 }
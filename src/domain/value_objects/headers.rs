@@ -17,10 +17,42 @@ impl Headers {
         self.0.insert(key.into(), value.into());
     }
 
+    /// 设置请求头，替换名称只有大小写不同的已有请求头
+    pub fn insert_ignore_case(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        self.0.retain(|k, _| !k.eq_ignore_ascii_case(&key));
+        self.0.insert(key, value.into());
+    }
+
     pub fn get(&self, key: &str) -> Option<&String> {
         self.0.get(key)
     }
 
+    /// 忽略大小写查找请求头
+    pub fn get_ignore_case(&self, key: &str) -> Option<&String> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// 追加同名头的值：按 RFC 9110 以逗号合并，`Set-Cookie` 不能合并，改用换行分隔
+    pub fn append(&mut self, key: impl Into<String>, value: impl AsRef<str>) {
+        let key = key.into();
+        let separator = if key.eq_ignore_ascii_case("set-cookie") {
+            "\n"
+        } else {
+            ", "
+        };
+        self.0
+            .entry(key)
+            .and_modify(|existing| {
+                existing.push_str(separator);
+                existing.push_str(value.as_ref());
+            })
+            .or_insert_with(|| value.as_ref().to_string());
+    }
+
     pub fn contains_key(&self, key: &str) -> bool {
         self.0.contains_key(key)
     }
@@ -0,0 +1,145 @@
+//! JSON 路径
+//!
+//! 支持 JSONPath 的常用子集：`$.data.items[0].id`、`$['key']`，开头的 `$` 可省略。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// 取出路径指向的值
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                Segment::Key(key) => current.get(key),
+                Segment::Index(index) => current.get(index),
+            })
+    }
+
+    /// 取出路径指向的值并转为字符串，字符串值不带引号
+    pub fn select_string(&self, value: &Value) -> Option<String> {
+        self.select(value).map(|selected| match selected {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid JSON path: {}", s);
+        let mut rest = s.trim().strip_prefix('$').unwrap_or(s.trim());
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(invalid)?;
+                let inner = after[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|k| k.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')));
+                segments.push(match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None => Segment::Index(inner.parse().map_err(|_| invalid())?),
+                });
+                rest = &after[end + 1..];
+            } else {
+                let after = rest.strip_prefix('.').unwrap_or(rest);
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(Segment::Key(after[..end].to_string()));
+                rest = &after[end..];
+            }
+        }
+
+        Ok(Self {
+            raw: s.to_string(),
+            segments,
+        })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+impl TryFrom<String> for JsonPath {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<JsonPath> for String {
+    fn from(path: JsonPath) -> Self {
+        path.raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(path: &str, value: &Value) -> Option<String> {
+        path.parse::<JsonPath>().unwrap().select_string(value)
+    }
+
+    #[test]
+    fn selects_nested_keys_and_indices() {
+        let value = json!({
+            "data": { "token": "abc", "items": [{ "id": 42 }, { "id": 43 }] },
+            "odd key": true,
+        });
+        assert_eq!(select("$.data.token", &value).as_deref(), Some("abc"));
+        assert_eq!(select("data.token", &value).as_deref(), Some("abc"));
+        assert_eq!(select("$.data.items[1].id", &value).as_deref(), Some("43"));
+        assert_eq!(select("$['odd key']", &value).as_deref(), Some("true"));
+        assert_eq!(
+            select("$[\"data\"].items[0]", &value).as_deref(),
+            Some(r#"{"id":42}"#)
+        );
+        assert_eq!(select("$.data.items[5]", &value), None);
+        assert_eq!(select("$.missing", &value), None);
+    }
+
+    #[test]
+    fn rejects_malformed_paths() {
+        for path in ["$.data[", "$.items[x]", "$..data", "$.data."] {
+            assert!(path.parse::<JsonPath>().is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn keeps_original_text() {
+        let path: JsonPath = " $.data.token".parse().unwrap();
+        assert_eq!(path.to_string(), " $.data.token");
+    }
+}
@@ -0,0 +1,490 @@
+//! 延迟直方图
+//!
+//! HDR 风格的对数-线性分桶直方图：内存占用只取决于精度和可追踪上限，
+//! 与样本数量无关；相同配置的直方图可以无损合并。
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 默认有效数字位数
+pub const DEFAULT_SIGNIFICANT_FIGURES: u8 = 3;
+
+/// 有效数字位数的上限
+///
+/// 按默认可追踪上限，每个直方图在 3 位时约占 190KB，4 位时约 2.4MB，5 位时约 17MB；
+/// 一份结果包含多个直方图，并且分阶段、分请求各有一份。
+pub const MAX_SIGNIFICANT_FIGURES: u8 = 4;
+
+/// 默认可追踪的最大延迟
+pub const DEFAULT_HIGHEST_TRACKABLE: Duration = Duration::from_secs(3600);
+
+/// 单次协调遗漏补记最多写入的取值个数
+const MAX_BACKFILL_RECORDS: u64 = 1000;
+
+/// 延迟直方图（单位：微秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "HistogramRepr", from = "HistogramRepr")]
+pub struct LatencyHistogram {
+    significant_figures: u8,
+    highest_trackable_us: u64,
+    sub_bucket_half_count_magnitude: u32,
+    sub_bucket_half_count: u64,
+    sub_bucket_mask: u64,
+    counts: Vec<u64>,
+    total_count: u64,
+    total_us: u128,
+    min_us: u64,
+    max_us: u64,
+}
+
+impl LatencyHistogram {
+    /// 使用默认可追踪上限创建直方图
+    ///
+    /// `significant_figures` 会被限制在 1..=`MAX_SIGNIFICANT_FIGURES` 之间。
+    pub fn new(significant_figures: u8) -> Self {
+        Self::with_highest_trackable(DEFAULT_HIGHEST_TRACKABLE, significant_figures)
+    }
+
+    /// 指定可追踪上限创建直方图，超过上限的样本按上限记录
+    pub fn with_highest_trackable(highest: Duration, significant_figures: u8) -> Self {
+        let significant_figures = significant_figures.clamp(1, MAX_SIGNIFICANT_FIGURES);
+        let highest_trackable_us = (highest.as_micros() as u64).max(2);
+
+        let largest_single_unit = 2 * 10u64.pow(significant_figures as u32);
+        let sub_bucket_count_magnitude = 64 - (largest_single_unit - 1).leading_zeros();
+        let sub_bucket_half_count_magnitude = sub_bucket_count_magnitude - 1;
+        let sub_bucket_count = 1u64 << sub_bucket_count_magnitude;
+        let sub_bucket_half_count = sub_bucket_count / 2;
+
+        let mut smallest_untrackable = sub_bucket_count;
+        let mut bucket_count = 1u64;
+        while smallest_untrackable <= highest_trackable_us {
+            if smallest_untrackable > u64::MAX / 2 {
+                bucket_count += 1;
+                break;
+            }
+            smallest_untrackable <<= 1;
+            bucket_count += 1;
+        }
+
+        Self {
+            significant_figures,
+            highest_trackable_us,
+            sub_bucket_half_count_magnitude,
+            sub_bucket_half_count,
+            sub_bucket_mask: sub_bucket_count - 1,
+            counts: vec![0; ((bucket_count + 1) * sub_bucket_half_count) as usize],
+            total_count: 0,
+            total_us: 0,
+            min_us: u64::MAX,
+            max_us: 0,
+        }
+    }
+
+    pub fn significant_figures(&self) -> u8 {
+        self.significant_figures
+    }
+
+    pub fn highest_trackable(&self) -> Duration {
+        Duration::from_micros(self.highest_trackable_us)
+    }
+
+    /// 记录一个延迟样本
+    pub fn record(&mut self, latency: Duration) {
+        self.record_n(latency, 1);
+    }
+
+    /// 以相同的值记录 `count` 个样本
+    pub fn record_n(&mut self, latency: Duration, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let value = (latency.as_micros() as u64).min(self.highest_trackable_us);
+        let index = self.counts_index_for(value);
+        self.counts[index] += count;
+        self.total_count += count;
+        self.total_us += value as u128 * count as u128;
+        self.min_us = self.min_us.min(value);
+        self.max_us = self.max_us.max(value);
+    }
+
+    /// 记录样本并补齐被协调遗漏（coordinated omission）掉的样本
+    ///
+    /// `latency` 应为实际发送到收到响应的耗时。当它超过预期发送间隔时，期间本应发出却被阻塞的
+    /// 请求也会经历类似的等待，依次补记 `latency - interval`、`latency - 2 * interval` …
+    /// 直到小于间隔。补记样本过多时相邻的若干个合并为一组，按组内最大值记录。
+    pub fn record_corrected(&mut self, latency: Duration, expected_interval: Duration) {
+        self.record(latency);
+        let interval_us = expected_interval.as_micros() as u64;
+        if interval_us == 0 {
+            return;
+        }
+        let value_us = (latency.as_micros() as u64).min(self.highest_trackable_us);
+        let missing = (value_us / interval_us).saturating_sub(1);
+        let group = missing.div_ceil(MAX_BACKFILL_RECORDS).max(1);
+        let mut step = 1;
+        while step <= missing {
+            let count = group.min(missing - step + 1);
+            self.record_n(Duration::from_micros(value_us - step * interval_us), count);
+            step += group;
+        }
+    }
+
+    /// 合并另一个直方图的样本
+    ///
+    /// 配置相同时逐桶相加；否则按对方每个桶的代表值重新记录。
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.total_count == 0 {
+            return;
+        }
+        if self.significant_figures == other.significant_figures
+            && self.counts.len() == other.counts.len()
+        {
+            for (mine, theirs) in self.counts.iter_mut().zip(&other.counts) {
+                *mine += theirs;
+            }
+            self.total_count += other.total_count;
+            self.total_us += other.total_us;
+            self.min_us = self.min_us.min(other.min_us);
+            self.max_us = self.max_us.max(other.max_us);
+        } else {
+            for (index, &count) in other.counts.iter().enumerate() {
+                if count > 0 {
+                    let value = other.value_from_index(index);
+                    self.record_n(Duration::from_micros(value), count);
+                }
+            }
+        }
+    }
+
+    /// 清空所有样本，保留配置
+    pub fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.total_count = 0;
+        self.total_us = 0;
+        self.min_us = u64::MAX;
+        self.max_us = 0;
+    }
+
+    pub fn len(&self) -> u64 {
+        self.total_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_count == 0
+    }
+
+    pub fn min(&self) -> Duration {
+        if self.is_empty() {
+            Duration::ZERO
+        } else {
+            Duration::from_micros(self.min_us)
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max_us)
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.is_empty() {
+            Duration::ZERO
+        } else {
+            Duration::from_micros((self.total_us / self.total_count as u128) as u64)
+        }
+    }
+
+    /// 计算分位数对应的延迟，`quantile` 取值 0.0..=1.0
+    pub fn value_at_quantile(&self, quantile: f64) -> Duration {
+        if self.is_empty() {
+            return Duration::ZERO;
+        }
+        let quantile = quantile.clamp(0.0, 1.0);
+        // 去掉 0.999 这类十进制分位在二进制表示下多出的尾数，避免排名被多算一位
+        let rank = quantile * self.total_count as f64 * (1.0 - f64::EPSILON);
+        let target = (rank.ceil() as u64).max(1);
+
+        let mut cumulative = 0u64;
+        for (index, &count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                let value = self.highest_equivalent_value(self.value_from_index(index));
+                return Duration::from_micros(value.clamp(self.min_us, self.max_us));
+            }
+        }
+        self.max()
+    }
+
+    /// 计算百分位数对应的延迟，`percentile` 取值 0.0..=100.0
+    pub fn value_at_percentile(&self, percentile: f64) -> Duration {
+        self.value_at_quantile(percentile / 100.0)
+    }
+
+    /// 确定小于 `value` 的样本数：所在桶的整个取值范围都小于 `value`
+    pub fn count_below(&self, value: Duration) -> u64 {
+        let value = value.as_micros() as u64;
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                self.highest_equivalent_value(self.value_from_index(*index)) < value
+            })
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// 确定大于 `value` 的样本数：所在桶的整个取值范围都大于 `value`
+    pub fn count_above(&self, value: Duration) -> u64 {
+        let value = value.as_micros() as u64;
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.value_from_index(*index) > value)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// 遍历非空桶，返回 (桶代表值, 样本数)
+    pub fn iter_recorded(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (Duration::from_micros(self.value_from_index(index)), *count))
+    }
+
+    fn bucket_index(&self, value: u64) -> u32 {
+        let leading_zero_count_base = 64 - self.sub_bucket_half_count_magnitude - 1;
+        leading_zero_count_base - (value | self.sub_bucket_mask).leading_zeros()
+    }
+
+    fn counts_index_for(&self, value: u64) -> usize {
+        let bucket_index = self.bucket_index(value);
+        let sub_bucket_index = value >> bucket_index;
+        let bucket_base = ((bucket_index + 1) as u64) << self.sub_bucket_half_count_magnitude;
+        (bucket_base + sub_bucket_index - self.sub_bucket_half_count) as usize
+    }
+
+    fn value_from_index(&self, index: usize) -> u64 {
+        let index = index as u64;
+        let mut bucket_index = (index >> self.sub_bucket_half_count_magnitude) as i64 - 1;
+        let mut sub_bucket_index =
+            (index & (self.sub_bucket_half_count - 1)) + self.sub_bucket_half_count;
+        if bucket_index < 0 {
+            sub_bucket_index -= self.sub_bucket_half_count;
+            bucket_index = 0;
+        }
+        sub_bucket_index << bucket_index
+    }
+
+    fn highest_equivalent_value(&self, value: u64) -> u64 {
+        let bucket_index = self.bucket_index(value);
+        let sub_bucket_index = value >> bucket_index;
+        let adjusted_bucket = if sub_bucket_index > self.sub_bucket_mask {
+            bucket_index + 1
+        } else {
+            bucket_index
+        };
+        let lowest = sub_bucket_index << bucket_index;
+        lowest + (1u64 << adjusted_bucket) - 1
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIGNIFICANT_FIGURES)
+    }
+}
+
+/// 序列化表示：只保存非空桶，避免导出文件随精度膨胀
+#[derive(Serialize, Deserialize)]
+struct HistogramRepr {
+    significant_figures: u8,
+    highest_trackable_us: u64,
+    min_us: u64,
+    max_us: u64,
+    total_us: u64,
+    counts: Vec<(u64, u64)>,
+}
+
+impl From<LatencyHistogram> for HistogramRepr {
+    fn from(histogram: LatencyHistogram) -> Self {
+        Self {
+            significant_figures: histogram.significant_figures,
+            highest_trackable_us: histogram.highest_trackable_us,
+            min_us: histogram.min_us,
+            max_us: histogram.max_us,
+            total_us: histogram.total_us.min(u64::MAX as u128) as u64,
+            counts: histogram
+                .iter_recorded()
+                .map(|(value, count)| (value.as_micros() as u64, count))
+                .collect(),
+        }
+    }
+}
+
+impl From<HistogramRepr> for LatencyHistogram {
+    fn from(repr: HistogramRepr) -> Self {
+        let mut histogram = Self::with_highest_trackable(
+            Duration::from_micros(repr.highest_trackable_us),
+            repr.significant_figures,
+        );
+        for (value, count) in repr.counts {
+            histogram.record_n(Duration::from_micros(value), count);
+        }
+        if !histogram.is_empty() {
+            histogram.min_us = repr.min_us;
+            histogram.max_us = repr.max_us;
+            histogram.total_us = repr.total_us as u128;
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform(significant_figures: u8) -> LatencyHistogram {
+        let mut histogram = LatencyHistogram::new(significant_figures);
+        for ms in 1..=1000 {
+            histogram.record(Duration::from_millis(ms));
+        }
+        histogram
+    }
+
+    fn assert_close(actual: Duration, expected: Duration) {
+        let error = actual.as_secs_f64() - expected.as_secs_f64();
+        assert!(
+            error.abs() <= expected.as_secs_f64() * 0.001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn quantiles_stay_within_precision() {
+        let histogram = uniform(3);
+        assert_eq!(histogram.len(), 1000);
+        assert_eq!(histogram.min(), Duration::from_millis(1));
+        assert_eq!(histogram.max(), Duration::from_millis(1000));
+        assert_close(histogram.mean(), Duration::from_micros(500_500));
+        assert_close(
+            histogram.value_at_percentile(50.0),
+            Duration::from_millis(500),
+        );
+        assert_close(
+            histogram.value_at_percentile(99.0),
+            Duration::from_millis(990),
+        );
+        assert_close(
+            histogram.value_at_percentile(99.9),
+            Duration::from_millis(999),
+        );
+        assert_eq!(
+            histogram.value_at_percentile(100.0),
+            Duration::from_millis(1000)
+        );
+        assert_eq!(histogram.value_at_quantile(0.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn empty_histogram_reports_zero() {
+        let histogram = LatencyHistogram::default();
+        assert!(histogram.is_empty());
+        assert_eq!(histogram.min(), Duration::ZERO);
+        assert_eq!(histogram.mean(), Duration::ZERO);
+        assert_eq!(histogram.value_at_percentile(99.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn merge_adds_samples() {
+        let mut merged = LatencyHistogram::new(3);
+        let mut other = LatencyHistogram::new(3);
+        for ms in 1..=1000 {
+            if ms % 2 == 0 {
+                merged.record(Duration::from_millis(ms));
+            } else {
+                other.record(Duration::from_millis(ms));
+            }
+        }
+        merged.merge(&other);
+        let expected = uniform(3);
+        assert_eq!(merged.len(), expected.len());
+        assert_eq!(merged.min(), expected.min());
+        assert_eq!(merged.max(), expected.max());
+        for percentile in [50.0, 90.0, 99.0, 99.9] {
+            assert_eq!(
+                merged.value_at_percentile(percentile),
+                expected.value_at_percentile(percentile)
+            );
+        }
+    }
+
+    #[test]
+    fn merge_across_precisions_re_records() {
+        let mut merged = LatencyHistogram::new(2);
+        merged.merge(&uniform(3));
+        assert_eq!(merged.len(), 1000);
+        let p50 = merged.value_at_percentile(50.0).as_secs_f64();
+        assert!((p50 - 0.5).abs() <= 0.5 * 0.01, "{}", p50);
+    }
+
+    #[test]
+    fn precision_is_capped() {
+        assert_eq!(LatencyHistogram::new(0).significant_figures(), 1);
+        assert_eq!(
+            LatencyHistogram::new(9).significant_figures(),
+            MAX_SIGNIFICANT_FIGURES
+        );
+        let bytes = LatencyHistogram::new(MAX_SIGNIFICANT_FIGURES).counts.len() * 8;
+        assert!(bytes < 3 << 20, "{}", bytes);
+    }
+
+    #[test]
+    fn counts_below_and_above_whole_buckets() {
+        let histogram = uniform(3);
+        assert_eq!(histogram.count_below(Duration::from_millis(100)), 99);
+        assert_eq!(histogram.count_above(Duration::from_millis(900)), 100);
+        assert_eq!(histogram.count_below(Duration::ZERO), 0);
+        assert_eq!(histogram.count_above(Duration::from_secs(10)), 0);
+    }
+
+    #[test]
+    fn serde_round_trip_keeps_samples() {
+        let histogram = uniform(3);
+        let json = serde_json::to_string(&histogram).unwrap();
+        let restored: LatencyHistogram = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.len(), histogram.len());
+        assert_eq!(restored.min(), histogram.min());
+        assert_eq!(restored.max(), histogram.max());
+        assert_eq!(restored.mean(), histogram.mean());
+        assert_eq!(
+            restored.value_at_percentile(99.0),
+            histogram.value_at_percentile(99.0)
+        );
+    }
+
+    #[test]
+    fn record_corrected_backfills_missed_intervals() {
+        let mut histogram = LatencyHistogram::new(3);
+        histogram.record_corrected(Duration::from_millis(350), Duration::from_millis(100));
+        // 350、250、150
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram.min(), Duration::from_millis(150));
+
+        let mut histogram = LatencyHistogram::new(3);
+        histogram.record_corrected(Duration::from_millis(50), Duration::from_millis(100));
+        assert_eq!(histogram.len(), 1);
+    }
+
+    #[test]
+    fn record_corrected_caps_work_but_keeps_counts() {
+        let mut histogram = LatencyHistogram::new(3);
+        histogram.record_corrected(Duration::from_secs(3600), Duration::from_millis(100));
+        assert_eq!(histogram.len(), 36_000);
+        assert_eq!(histogram.max(), Duration::from_secs(3600));
+        assert!(histogram.iter_recorded().count() <= MAX_BACKFILL_RECORDS as usize + 1);
+    }
+}
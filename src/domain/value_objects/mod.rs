@@ -1,9 +1,17 @@
 pub use body::{Body, ContentType};
 pub use headers::{Headers, default_headers};
 pub use http_method::HttpMethod;
+pub use json_path::JsonPath;
+pub use latency_histogram::{
+    DEFAULT_SIGNIFICANT_FIGURES, LatencyHistogram, MAX_SIGNIFICANT_FIGURES,
+};
+pub use template::Variables;
 pub use url::{Url, UrlError};
 
 mod body;
 mod headers;
 mod http_method;
+mod json_path;
+mod latency_histogram;
+pub mod template;
 mod url;
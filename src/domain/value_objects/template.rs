@@ -0,0 +1,171 @@
+//! `{{name}}` 占位符模板
+//!
+//! 替换时按值所在的位置转义（见 `Escape`）；写成 `{{name | raw}}` 时原样插入，
+//! 例如变量本身就是一段 URL 或一段 JSON。
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// 虚拟用户的变量表
+pub type Variables = HashMap<String, String>;
+
+/// 不转义的过滤器名
+const RAW_FILTER: &str = "raw";
+
+/// 插入变量值时的转义方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// 原样插入
+    None,
+    /// 百分号编码，只保留 RFC 3986 的非保留字符，用于 URL 和表单
+    Url,
+    /// 按 JSON 字符串的内容转义（不加引号），用于 JSON 请求体
+    Json,
+}
+
+impl Escape {
+    fn push(self, output: &mut String, value: &str) {
+        match self {
+            Self::None => output.push_str(value),
+            Self::Url => {
+                for byte in value.bytes() {
+                    if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                        output.push(byte as char);
+                    } else {
+                        let _ = write!(output, "%{:02X}", byte);
+                    }
+                }
+            }
+            Self::Json => {
+                let quoted = serde_json::Value::from(value).to_string();
+                output.push_str(&quoted[1..quoted.len() - 1]);
+            }
+        }
+    }
+}
+
+/// 是否包含占位符
+pub fn has_placeholders(template: &str) -> bool {
+    placeholders(template).next().is_some()
+}
+
+/// 依次列出模板中引用的变量名
+pub fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    let mut rest = template;
+    std::iter::from_fn(move || {
+        let start = rest.find("{{")?;
+        let end = rest[start + 2..].find("}}")?;
+        let (name, _) = split_filter(&rest[start + 2..start + 2 + end]);
+        rest = &rest[start + 2 + end + 2..];
+        Some(name)
+    })
+}
+
+/// 用变量替换占位符，按 `escape` 转义插入的值
+///
+/// 引用未定义的变量或未知的过滤器时返回错误。
+pub fn render<'a>(
+    template: &'a str,
+    variables: &Variables,
+    escape: Escape,
+) -> Result<Cow<'a, str>, String> {
+    if !has_placeholders(template) {
+        return Ok(Cow::Borrowed(template));
+    }
+
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let (name, filter) = split_filter(&rest[start + 2..start + 2 + end]);
+        let escape = match filter {
+            None => escape,
+            Some(RAW_FILTER) => Escape::None,
+            Some(filter) => return Err(format!("未知的过滤器: {}", filter)),
+        };
+        let value = variables
+            .get(name)
+            .ok_or_else(|| format!("未定义的变量: {}", name))?;
+        output.push_str(&rest[..start]);
+        escape.push(&mut output, value);
+        rest = &rest[start + 2 + end + 2..];
+    }
+    output.push_str(rest);
+    Ok(Cow::Owned(output))
+}
+
+/// 拆分 `name | filter`
+fn split_filter(placeholder: &str) -> (&str, Option<&str>) {
+    match placeholder.split_once('|') {
+        Some((name, filter)) => (name.trim(), Some(filter.trim())),
+        None => (placeholder.trim(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> Variables {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_placeholders() {
+        let names: Vec<_> = placeholders("/users/{{ id }}?q={{term|raw}}&x={{id}}").collect();
+        assert_eq!(names, ["id", "term", "id"]);
+        assert!(!has_placeholders("/users/{id}"));
+        assert!(!has_placeholders("/users/{{id"));
+    }
+
+    #[test]
+    fn renders_without_placeholders_borrowed() {
+        let rendered = render("/health", &Variables::new(), Escape::Url).unwrap();
+        assert!(matches!(rendered, Cow::Borrowed("/health")));
+    }
+
+    #[test]
+    fn escapes_by_position() {
+        let vars = variables(&[("term", "a b&c/é"), ("name", "say \"hi\"\n")]);
+        assert_eq!(
+            render("/search?q={{term}}", &vars, Escape::Url).unwrap(),
+            "/search?q=a%20b%26c%2F%C3%A9"
+        );
+        assert_eq!(
+            render(r#"{"name": "{{name}}"}"#, &vars, Escape::Json).unwrap(),
+            r#"{"name": "say \"hi\"\n"}"#
+        );
+        assert_eq!(
+            render("{{name}}", &vars, Escape::None).unwrap(),
+            "say \"hi\"\n"
+        );
+    }
+
+    #[test]
+    fn raw_filter_skips_escaping() {
+        let vars = variables(&[("base", "http://example.com/api")]);
+        assert_eq!(
+            render("{{ base | raw }}/users", &vars, Escape::Url).unwrap(),
+            "http://example.com/api/users"
+        );
+    }
+
+    #[test]
+    fn reports_undefined_variables_and_unknown_filters() {
+        let vars = variables(&[("id", "1")]);
+        assert_eq!(
+            render("/users/{{missing}}", &vars, Escape::Url).unwrap_err(),
+            "未定义的变量: missing"
+        );
+        assert_eq!(
+            render("/users/{{id | upper}}", &vars, Escape::Url).unwrap_err(),
+            "未知的过滤器: upper"
+        );
+    }
+}
@@ -0,0 +1,701 @@
+//! 压测结果导出
+//!
+//! `json` 为完整的 `LoadTestResult`；`csv` 为总体、各阶段和各请求的汇总表；
+//! `ndjson` 为逐请求的原始记录，每行一个 JSON 对象，由后台线程写入；
+//! `html` 为可离线查看的单文件报告；`junit` 为阈值和响应检查的 JUnit XML；
+//! `timeseries-csv` 和 `timeseries-ndjson` 为按秒分桶的时间序列，每秒一行。
+
+use super::{html_report, junit_report};
+use crate::domain::entities::{
+    LoadTestConfig, LoadTestResult, RequestRecord, TimeBucket, TimeSeries,
+};
+use crate::domain::services::RequestLog;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Failed to write {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to serialize result: {0}")]
+    Serialize(String),
+
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {message}")]
+    Parse { path: String, message: String },
+}
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Ndjson,
+    Html,
+    Junit,
+    TimeseriesCsv,
+    TimeseriesNdjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            "html" => Ok(Self::Html),
+            "junit" => Ok(Self::Junit),
+            "timeseries-csv" => Ok(Self::TimeseriesCsv),
+            "timeseries-ndjson" | "timeseries-jsonl" => Ok(Self::TimeseriesNdjson),
+            _ => Err(format!("Invalid output format: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
+            Self::Ndjson => write!(f, "ndjson"),
+            Self::Html => write!(f, "html"),
+            Self::Junit => write!(f, "junit"),
+            Self::TimeseriesCsv => write!(f, "timeseries-csv"),
+            Self::TimeseriesNdjson => write!(f, "timeseries-ndjson"),
+        }
+    }
+}
+
+/// 一个导出目标，命令行写法为 `格式=路径`，例如 `json=result.json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTarget {
+    pub format: OutputFormat,
+    pub path: PathBuf,
+}
+
+impl FromStr for OutputTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("导出目标格式应为 格式=路径，实际为: {}", s))?;
+        if path.is_empty() {
+            return Err(format!("导出目标缺少路径: {}", s));
+        }
+        Ok(Self {
+            format: format.trim().parse()?,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// 把最终结果写到导出目标；`ndjson` 在压测过程中由 `NdjsonRequestLog` 写入，这里不做处理
+pub fn write_result(
+    target: &OutputTarget,
+    config: &LoadTestConfig,
+    result: &LoadTestResult,
+) -> Result<(), ExportError> {
+    match target.format {
+        OutputFormat::Json => write_json(&target.path, result),
+        OutputFormat::Csv => write_csv(&target.path, result),
+        OutputFormat::Ndjson => Ok(()),
+        OutputFormat::Html => write_html(&target.path, config, result),
+        OutputFormat::Junit => write_junit(&target.path, config, result),
+        OutputFormat::TimeseriesCsv => write_time_series_csv(&target.path, &result.time_series),
+        OutputFormat::TimeseriesNdjson => {
+            write_time_series_ndjson(&target.path, &result.time_series)
+        }
+    }
+}
+
+pub fn write_json(path: impl AsRef<Path>, result: &LoadTestResult) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mut writer = create(path)?;
+    serde_json::to_writer_pretty(&mut writer, result)
+        .map_err(|e| ExportError::Serialize(e.to_string()))?;
+    writer
+        .write_all(b"\n")
+        .and_then(|_| writer.flush())
+        .map_err(|source| io_error(path, source))
+}
+
+/// 读取 `json` 格式导出的结果
+pub fn read_json(path: impl AsRef<Path>) -> Result<LoadTestResult, ExportError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| ExportError::Read {
+        path: path.display().to_string(),
+        source,
+    })?;
+    serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| ExportError::Parse {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+pub fn write_html(
+    path: impl AsRef<Path>,
+    config: &LoadTestConfig,
+    result: &LoadTestResult,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    std::fs::write(path, html_report::render(config, result))
+        .map_err(|source| io_error(path, source))
+}
+
+pub fn write_junit(
+    path: impl AsRef<Path>,
+    config: &LoadTestConfig,
+    result: &LoadTestResult,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    std::fs::write(path, junit_report::render(config, result))
+        .map_err(|source| io_error(path, source))
+}
+
+/// CSV 汇总表的一行，延迟单位为毫秒
+#[derive(Serialize)]
+struct SummaryRow<'a> {
+    /// `total`、`stage` 或 `request`
+    scope: &'a str,
+    name: &'a str,
+    total_requests: u64,
+    successful_requests: u64,
+    failed_requests: u64,
+    status_1xx: u64,
+    status_2xx: u64,
+    status_3xx: u64,
+    status_4xx: u64,
+    status_5xx: u64,
+    avg_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p95_ms: f64,
+    p99_ms: f64,
+    p999_ms: f64,
+    elapsed_secs: f64,
+    qps: f64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    sent_bytes_per_sec: f64,
+    received_bytes_per_sec: f64,
+}
+
+impl<'a> SummaryRow<'a> {
+    fn new(scope: &'a str, name: &'a str, result: &LoadTestResult) -> Self {
+        let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+        Self {
+            scope,
+            name,
+            total_requests: result.total_requests,
+            successful_requests: result.successful_requests,
+            failed_requests: result.failed_requests,
+            status_1xx: result.status_1xx,
+            status_2xx: result.status_2xx,
+            status_3xx: result.status_3xx,
+            status_4xx: result.status_4xx,
+            status_5xx: result.status_5xx,
+            avg_ms: millis(result.avg_latency),
+            // 没有请求时最小延迟保持初始值 `Duration::MAX`
+            min_ms: if result.total_requests == 0 {
+                0.0
+            } else {
+                millis(result.min_latency)
+            },
+            max_ms: millis(result.max_latency),
+            p50_ms: millis(result.p50_latency),
+            p90_ms: millis(result.p90_latency),
+            p95_ms: millis(result.p95_latency),
+            p99_ms: millis(result.p99_latency),
+            p999_ms: millis(result.p999_latency),
+            elapsed_secs: result.elapsed.as_secs_f64(),
+            qps: result.qps,
+            bytes_sent: result.bytes_sent,
+            bytes_received: result.bytes_received,
+            sent_bytes_per_sec: result.sent_bytes_per_sec,
+            received_bytes_per_sec: result.received_bytes_per_sec,
+        }
+    }
+}
+
+pub fn write_csv(path: impl AsRef<Path>, result: &LoadTestResult) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mut writer = csv::Writer::from_writer(create(path)?);
+
+    let mut rows = vec![SummaryRow::new("total", "", result)];
+    rows.extend(
+        result
+            .stage_results
+            .iter()
+            .map(|stage| SummaryRow::new("stage", &stage.name, &stage.result)),
+    );
+    rows.extend(
+        result
+            .request_results
+            .iter()
+            .map(|request| SummaryRow::new("request", &request.name, &request.result)),
+    );
+
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|e| ExportError::Serialize(e.to_string()))?;
+    }
+    writer.flush().map_err(|source| io_error(path, source))
+}
+
+/// 时间序列中一秒的汇总，延迟单位为毫秒
+#[derive(Serialize)]
+struct SeriesRow<'a> {
+    second: u64,
+    requests: u64,
+    errors: u64,
+    bytes: u64,
+    avg_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+    errors_by_kind: &'a BTreeMap<String, u64>,
+}
+
+impl<'a> SeriesRow<'a> {
+    fn new(bucket: &'a TimeBucket) -> Self {
+        let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+        let latency = &bucket.latency;
+        Self {
+            second: bucket.second,
+            requests: bucket.requests,
+            errors: bucket.errors,
+            bytes: bucket.bytes,
+            avg_ms: millis(latency.mean()),
+            p50_ms: millis(latency.value_at_percentile(50.0)),
+            p90_ms: millis(latency.value_at_percentile(90.0)),
+            p99_ms: millis(latency.value_at_percentile(99.0)),
+            max_ms: millis(latency.max()),
+            errors_by_kind: &bucket.errors_by_kind,
+        }
+    }
+}
+
+/// 时间序列写成 CSV，每个错误类别单独一列 `errors_<类别>`
+pub fn write_time_series_csv(
+    path: impl AsRef<Path>,
+    time_series: &TimeSeries,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mut writer = csv::Writer::from_writer(create(path)?);
+    let kinds = time_series.error_kinds();
+
+    let mut header: Vec<String> = [
+        "second", "requests", "errors", "bytes", "avg_ms", "p50_ms", "p90_ms", "p99_ms", "max_ms",
+    ]
+    .iter()
+    .map(|column| column.to_string())
+    .collect();
+    header.extend(kinds.iter().map(|kind| format!("errors_{}", kind)));
+    writer
+        .write_record(&header)
+        .map_err(|e| ExportError::Serialize(e.to_string()))?;
+
+    for bucket in &time_series.buckets {
+        let row = SeriesRow::new(bucket);
+        let mut record = vec![
+            row.second.to_string(),
+            row.requests.to_string(),
+            row.errors.to_string(),
+            row.bytes.to_string(),
+            format!("{:.3}", row.avg_ms),
+            format!("{:.3}", row.p50_ms),
+            format!("{:.3}", row.p90_ms),
+            format!("{:.3}", row.p99_ms),
+            format!("{:.3}", row.max_ms),
+        ];
+        record.extend(kinds.iter().map(|&kind| {
+            row.errors_by_kind
+                .get(kind)
+                .copied()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        writer
+            .write_record(&record)
+            .map_err(|e| ExportError::Serialize(e.to_string()))?;
+    }
+    writer.flush().map_err(|source| io_error(path, source))
+}
+
+/// 时间序列写成 NDJSON，每秒一个 JSON 对象
+pub fn write_time_series_ndjson(
+    path: impl AsRef<Path>,
+    time_series: &TimeSeries,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let mut writer = create(path)?;
+    for bucket in &time_series.buckets {
+        serde_json::to_writer(&mut writer, &SeriesRow::new(bucket))
+            .map_err(|e| ExportError::Serialize(e.to_string()))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|source| io_error(path, source))?;
+    }
+    writer.flush().map_err(|source| io_error(path, source))
+}
+
+/// NDJSON 写入线程前排队的记录上限
+const NDJSON_QUEUE_CAPACITY: usize = 10000;
+
+/// 逐请求写入 NDJSON 文件
+///
+/// 记录经有界通道交给后台线程序列化和写入，压测的汇总循环只做一次发送。
+/// 磁盘跟不上时队列满后的记录直接丢弃，计入 `dropped`。
+pub struct NdjsonRequestLog {
+    path: PathBuf,
+    sender: Mutex<Option<mpsc::SyncSender<RequestRecord>>>,
+    writer: Mutex<Option<JoinHandle<std::io::Result<()>>>>,
+    dropped: AtomicU64,
+}
+
+impl NdjsonRequestLog {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let path = path.as_ref().to_path_buf();
+        let file = create(&path)?;
+        Ok(Self::spawn(path, file, NDJSON_QUEUE_CAPACITY))
+    }
+
+    /// 启动写入线程，`path` 只用于错误信息
+    fn spawn(path: PathBuf, mut out: impl Write + Send + 'static, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<RequestRecord>(capacity);
+
+        let writer = std::thread::spawn(move || {
+            for record in receiver {
+                serde_json::to_writer(&mut out, &record)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()
+        });
+
+        Self {
+            path,
+            sender: Mutex::new(Some(sender)),
+            writer: Mutex::new(Some(writer)),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// 因队列已满而丢弃的记录数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl RequestLog for NdjsonRequestLog {
+    fn record(&self, record: RequestRecord) {
+        if let Some(sender) = self.sender.lock().unwrap().as_ref()
+            && let Err(mpsc::TrySendError::Full(_)) = sender.try_send(record)
+        {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        // 关闭通道后写入线程处理完剩余记录即退出
+        self.sender.lock().unwrap().take();
+        let Some(writer) = self.writer.lock().unwrap().take() else {
+            return Ok(());
+        };
+        writer
+            .join()
+            .map_err(|_| {
+                format!(
+                    "Failed to write {}: writer thread panicked",
+                    self.path.display()
+                )
+            })?
+            .map_err(|e| io_error(&self.path, e).to_string())
+    }
+}
+
+fn create(path: &Path) -> Result<BufWriter<File>, ExportError> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|source| io_error(path, source))
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> ExportError {
+    ExportError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// 转义 HTML / XML 文本和属性值中的特殊字符
+pub(crate) fn escape_markup(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{RequestResult, StageResult, StageTarget};
+    use std::sync::Arc;
+
+    fn record(request: &str) -> RequestRecord {
+        RequestRecord {
+            timestamp: chrono::Utc::now(),
+            request: request.to_string(),
+            status: 200,
+            latency: Duration::from_millis(12),
+            bytes: 64,
+            bytes_sent: 0,
+            error: None,
+            warmup: false,
+        }
+    }
+
+    fn sample_result() -> LoadTestResult {
+        let mut result = LoadTestResult::default();
+        result.add_response(200, Duration::from_millis(10), true);
+        result.add_response(500, Duration::from_millis(30), false);
+        result.add_error("connection", "Connection failed: refused");
+        result.bytes_received = 128;
+        result.elapsed = Duration::from_secs(2);
+        result.calculate_qps();
+        result.calculate_percentiles();
+        result.stage_results.push(StageResult {
+            name: "steady".to_string(),
+            target: StageTarget::Users(2),
+            duration_secs: 2,
+            result: result.clone(),
+        });
+        result.request_results.push(RequestResult {
+            name: "GET /users".to_string(),
+            share: None,
+            result: result.clone(),
+        });
+        result.time_series.record(
+            Duration::from_millis(500),
+            Some(Duration::from_millis(10)),
+            64,
+            None,
+        );
+        result
+    }
+
+    #[test]
+    fn parses_output_targets() {
+        let target: OutputTarget = "JSON=out/result.json".parse().unwrap();
+        assert_eq!(target.format, OutputFormat::Json);
+        assert_eq!(target.path, PathBuf::from("out/result.json"));
+        // 只按第一个 `=` 拆分，路径中可以包含 `=`
+        let target: OutputTarget = "jsonl=a=b.ndjson".parse().unwrap();
+        assert_eq!(target.format, OutputFormat::Ndjson);
+        assert_eq!(target.path, PathBuf::from("a=b.ndjson"));
+        let target: OutputTarget = "timeseries-csv=ts.csv".parse().unwrap();
+        assert_eq!(target.format, OutputFormat::TimeseriesCsv);
+
+        assert!("result.json".parse::<OutputTarget>().is_err());
+        assert!("json=".parse::<OutputTarget>().is_err());
+        assert!("xml=result.xml".parse::<OutputTarget>().is_err());
+    }
+
+    #[test]
+    fn json_export_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("result.json");
+        let result = sample_result();
+        write_json(&path, &result).unwrap();
+        let read = read_json(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&result).unwrap()
+        );
+        assert_eq!(read.latency_histogram.len(), 2);
+
+        assert!(matches!(
+            read_json(dir.path().join("missing.json")),
+            Err(ExportError::Read { .. })
+        ));
+        std::fs::write(&path, "{").unwrap();
+        assert!(matches!(read_json(&path), Err(ExportError::Parse { .. })));
+    }
+
+    #[test]
+    fn writes_summary_rows_for_total_stages_and_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summary.csv");
+        write_csv(&path, &sample_result()).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        assert_eq!(&header[0], "scope");
+        assert_eq!(&header[2], "total_requests");
+        assert!(
+            header
+                .iter()
+                .any(|column| column == "received_bytes_per_sec")
+        );
+
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        let scopes: Vec<(&str, &str)> = rows.iter().map(|row| (&row[0], &row[1])).collect();
+        assert_eq!(
+            scopes,
+            [
+                ("total", ""),
+                ("stage", "steady"),
+                ("request", "GET /users")
+            ]
+        );
+        for row in &rows {
+            assert_eq!(&row[2], "2");
+            assert_eq!(&row[3], "1");
+            assert_eq!(&row[4], "1");
+        }
+    }
+
+    #[test]
+    fn writes_a_column_per_error_kind_in_time_series_csv() {
+        let mut series = TimeSeries::new();
+        let ok = Some(Duration::from_millis(10));
+        series.record(Duration::from_millis(100), ok, 10, None);
+        series.record(Duration::from_millis(900), ok, 0, Some("timeout"));
+        series.record(Duration::from_millis(2500), None, 0, Some("connection"));
+        series.record(Duration::from_millis(2600), None, 0, Some("connection"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("series.csv");
+        write_time_series_csv(&path, &series).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        let header: Vec<&str> = header.iter().collect();
+        assert_eq!(&header[..4], ["second", "requests", "errors", "bytes"]);
+        assert_eq!(&header[9..], ["errors_connection", "errors_timeout"]);
+
+        let rows: Vec<Vec<String>> = reader
+            .records()
+            .map(|row| row.unwrap().iter().map(str::to_string).collect())
+            .collect();
+        let counts: Vec<[&str; 5]> = rows
+            .iter()
+            .map(|row| [&row[0], &row[1], &row[2], &row[9], &row[10]].map(String::as_str))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ["0", "2", "1", "0", "1"],
+                // 没有请求的秒保留空行
+                ["1", "0", "0", "0", "0"],
+                ["2", "2", "2", "2", "0"],
+            ]
+        );
+    }
+
+    #[test]
+    fn ndjson_log_flushes_every_record_on_finish() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.ndjson");
+        let log = NdjsonRequestLog::create(&path).unwrap();
+        for i in 0..100 {
+            log.record(record(&format!("request {i}")));
+        }
+        log.finish().unwrap();
+        // 结束后的记录被忽略，重复结束不报错
+        log.record(record("late"));
+        log.finish().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 100);
+        assert_eq!(lines[99]["request"], "request 99");
+        assert_eq!(lines[0]["latency_ms"], 12.0);
+        assert_eq!(log.dropped(), 0);
+    }
+
+    /// 第一次写入前一直阻塞，直到收到放行信号
+    struct GatedWriter {
+        gate: Option<mpsc::Receiver<()>>,
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Write for GatedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if let Some(gate) = self.gate.take() {
+                let _ = gate.recv();
+            }
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ndjson_log_drops_records_when_the_queue_is_full() {
+        let (release, gate) = mpsc::channel();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let out = GatedWriter {
+            gate: Some(gate),
+            written: written.clone(),
+        };
+        let log = NdjsonRequestLog::spawn(PathBuf::from("blocked.ndjson"), out, 2);
+        for i in 0..10 {
+            log.record(record(&format!("request {i}")));
+        }
+        // 写入线程最多拿走一条，队列中最多再排两条
+        assert!(log.dropped() >= 7, "{}", log.dropped());
+
+        release.send(()).unwrap();
+        log.finish().unwrap();
+        let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+        assert_eq!(written.lines().count() as u64, 10 - log.dropped());
+    }
+
+    #[test]
+    fn escapes_markup_characters() {
+        assert_eq!(
+            escape_markup(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape_markup("纯文本"), "纯文本");
+    }
+}
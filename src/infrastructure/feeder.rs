@@ -0,0 +1,192 @@
+//! 数据文件读取
+
+use crate::domain::entities::{FeedStrategy, Feeder};
+use crate::domain::value_objects::Variables;
+use serde_json::Value;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FeederError {
+    #[error("Failed to read data file {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Unsupported data file format: {0} (expected .csv, .jsonl, .ndjson or .json)")]
+    UnsupportedFormat(String),
+
+    #[error("Failed to parse data file {path}: {message}")]
+    Parse { path: String, message: String },
+
+    #[error("Invalid data file: {0}")]
+    Invalid(String),
+}
+
+/// 读取数据文件并创建数据源，名称取文件名
+pub fn load_feeder(path: impl AsRef<Path>, strategy: FeedStrategy) -> Result<Feeder, FeederError> {
+    let path = path.as_ref();
+    let rows = load_rows(path)?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    Feeder::new(name, rows, strategy).map_err(FeederError::Invalid)
+}
+
+/// 按扩展名读取数据行：CSV 首行为列名；JSON Lines 每行一个对象；JSON 为对象数组
+pub fn load_rows(path: impl AsRef<Path>) -> Result<Vec<Variables>, FeederError> {
+    let path = path.as_ref();
+    let display = path.display().to_string();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let parse_error = |message: String| FeederError::Parse {
+        path: display.clone(),
+        message,
+    };
+
+    let content = std::fs::read_to_string(path).map_err(|source| FeederError::Io {
+        path: display.clone(),
+        source,
+    })?;
+
+    match extension.as_str() {
+        "csv" => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|e| parse_error(e.to_string()))?
+                .clone();
+            reader
+                .records()
+                .map(|record| {
+                    let record = record.map_err(|e| parse_error(e.to_string()))?;
+                    Ok(headers
+                        .iter()
+                        .zip(record.iter())
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .collect())
+                })
+                .collect()
+        }
+        "jsonl" | "ndjson" => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let value = serde_json::from_str(line).map_err(|e| parse_error(e.to_string()))?;
+                json_row(value).map_err(parse_error)
+            })
+            .collect(),
+        "json" => match serde_json::from_str(&content).map_err(|e| parse_error(e.to_string()))? {
+            Value::Array(values) => values
+                .into_iter()
+                .map(|value| json_row(value).map_err(parse_error))
+                .collect(),
+            _ => Err(parse_error("顶层必须是对象数组".to_string())),
+        },
+        _ => Err(FeederError::UnsupportedFormat(display)),
+    }
+}
+
+/// 把 JSON 对象转成一行变量，字符串值不带引号，其余值保留 JSON 写法
+fn json_row(value: Value) -> Result<Variables, String> {
+    let Value::Object(object) = value else {
+        return Err(format!("每行数据必须是对象，实际为: {}", value));
+    };
+    Ok(object
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write(dir: &tempfile::TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn pairs(row: &Variables) -> Vec<(&str, &str)> {
+        let mut pairs: Vec<_> = row.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn reads_csv_with_header_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "users.csv", "id,name\n1,alice\n2,\"bob, jr\"\n");
+        let rows = load_rows(&path).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(pairs(&rows[1]), [("id", "2"), ("name", "bob, jr")]);
+
+        let feeder = load_feeder(&path, FeedStrategy::Unique).unwrap();
+        assert_eq!(feeder.name(), "users.csv");
+        assert_eq!(feeder.columns(), ["id", "name"]);
+    }
+
+    #[test]
+    fn reads_json_lines_and_arrays() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(
+            &dir,
+            "rows.JSONL",
+            "{\"id\": 1, \"name\": \"alice\"}\n\n{\"id\": 2, \"tags\": [\"a\"]}\n",
+        );
+        let rows = load_rows(&path).unwrap();
+        assert_eq!(pairs(&rows[0]), [("id", "1"), ("name", "alice")]);
+        assert_eq!(pairs(&rows[1]), [("id", "2"), ("tags", "[\"a\"]")]);
+
+        let path = write(&dir, "rows.json", r#"[{"id": "1"}, {"id": null}]"#);
+        let rows = load_rows(&path).unwrap();
+        assert_eq!(pairs(&rows[1]), [("id", "null")]);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cases = [
+            ("ragged.csv", "id,name\n1,alice\n2\n"),
+            ("broken.jsonl", "{\"id\": 1}\n{\"id\": \n"),
+            ("scalar.jsonl", "{\"id\": 1}\n42\n"),
+            ("object.json", r#"{"id": 1}"#),
+        ];
+        for (name, content) in cases {
+            let path = write(&dir, name, content);
+            assert!(
+                matches!(load_rows(&path), Err(FeederError::Parse { .. })),
+                "{}",
+                name
+            );
+        }
+
+        let path = write(&dir, "rows.txt", "id\n1\n");
+        assert!(matches!(
+            load_rows(&path),
+            Err(FeederError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            load_rows(dir.path().join("missing.csv")),
+            Err(FeederError::Io { .. })
+        ));
+        let path = write(&dir, "empty.csv", "id,name\n");
+        assert!(matches!(
+            load_feeder(&path, FeedStrategy::Circular),
+            Err(FeederError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,668 @@
+//! 单文件 HTML 报告
+//!
+//! 图表直接以内联 SVG 生成，不引用任何外部脚本、样式或字体，离线也能打开。
+
+use super::export::escape_markup as escape;
+use crate::domain::entities::{LoadTestConfig, LoadTestResult, TimeSeries, evaluate_thresholds};
+use std::fmt::Write;
+use std::time::Duration;
+
+const CHART_WIDTH: f64 = 860.0;
+const CHART_HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 28.0;
+const MARGIN_BOTTOM: f64 = 32.0;
+
+/// 延迟分布图的分组数
+const HISTOGRAM_BINS: usize = 40;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", "PingFang SC", "Microsoft YaHei", sans-serif; margin: 0; background: #f5f6f8; color: #222; }
+main { max-width: 920px; margin: 0 auto; padding: 24px; }
+h1 { margin: 0 0 4px; font-size: 24px; }
+h2 { margin: 32px 0 12px; font-size: 18px; border-bottom: 1px solid #ddd; padding-bottom: 6px; }
+.meta { color: #666; font-size: 13px; }
+.alert { background: #fdecea; color: #a12622; padding: 10px 14px; border-radius: 6px; margin-top: 16px; }
+.cards { display: grid; grid-template-columns: repeat(auto-fill, minmax(160px, 1fr)); gap: 12px; }
+.card { background: #fff; border-radius: 8px; padding: 12px 14px; box-shadow: 0 1px 2px rgba(0,0,0,.06); }
+.card .label { color: #666; font-size: 12px; }
+.card .value { font-size: 20px; font-weight: 600; margin-top: 4px; }
+table { width: 100%; border-collapse: collapse; background: #fff; border-radius: 8px; overflow: hidden; font-size: 13px; }
+th, td { padding: 7px 10px; text-align: right; border-bottom: 1px solid #eee; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #fafafa; font-weight: 600; }
+td.pass { color: #1a7f37; } td.fail { color: #c62828; }
+svg { background: #fff; border-radius: 8px; display: block; margin-bottom: 12px; }
+svg text { font-size: 11px; fill: #555; }
+"#;
+
+/// 按错误类别作图时依次使用的颜色
+const PALETTE: [&str; 6] = [
+    "#d62728", "#ff7f0e", "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22",
+];
+
+/// 一条折线
+struct Series<'a> {
+    name: &'a str,
+    color: &'static str,
+    points: Vec<(f64, f64)>,
+}
+
+/// 生成完整的 HTML 报告
+pub fn render(config: &LoadTestConfig, result: &LoadTestResult) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Bolt 负载测试报告</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n",
+        STYLE
+    );
+    let _ = write!(
+        html,
+        "<h1>Bolt 负载测试报告</h1>\n<div class=\"meta\">生成时间 {}</div>\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    if result.interrupted {
+        html.push_str("<div class=\"alert\">压测已中断，结果只包含中断前完成的请求</div>\n");
+    }
+    if let Some(reason) = &result.aborted {
+        let _ = writeln!(
+            html,
+            "<div class=\"alert\">压测已提前结束: {}</div>",
+            escape(reason)
+        );
+    }
+
+    render_summary(&mut html, result);
+    render_status_codes(&mut html, result);
+    render_thresholds(&mut html, config, result);
+    render_time_series(&mut html, &result.time_series);
+    render_histogram(&mut html, result);
+    render_requests(&mut html, result);
+    render_config(&mut html, config);
+
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn render_summary(html: &mut String, result: &LoadTestResult) {
+    let success_rate = if result.total_requests > 0 {
+        result.successful_requests as f64 / result.total_requests as f64 * 100.0
+    } else {
+        0.0
+    };
+    let cards = [
+        ("总请求数", result.total_requests.to_string()),
+        ("成功率", format!("{:.2}%", success_rate)),
+        ("失败请求", result.failed_requests.to_string()),
+        ("QPS", format!("{:.1}", result.qps)),
+        (
+            "响应体带宽",
+            format!("{:.1} KB/秒", result.received_bytes_per_sec / 1024.0),
+        ),
+        (
+            "请求体带宽",
+            format!("{:.1} KB/秒", result.sent_bytes_per_sec / 1024.0),
+        ),
+        ("平均延迟", format_duration(result.avg_latency)),
+        ("P50 延迟", format_duration(result.p50_latency)),
+        ("P95 延迟", format_duration(result.p95_latency)),
+        ("P99 延迟", format_duration(result.p99_latency)),
+        ("P99.9 延迟", format_duration(result.p999_latency)),
+        ("最大延迟", format_duration(result.max_latency)),
+    ];
+
+    html.push_str("<h2>摘要</h2>\n<div class=\"cards\">\n");
+    for (label, value) in cards {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><div class=\"label\">{}</div><div class=\"value\">{}</div></div>",
+            label, value
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+fn render_thresholds(html: &mut String, config: &LoadTestConfig, result: &LoadTestResult) {
+    let verdicts = evaluate_thresholds(&config.thresholds, result);
+    if verdicts.is_empty() {
+        return;
+    }
+
+    html.push_str("<h2>阈值</h2>\n<table>\n<tr><th>阈值</th><th>实际</th><th>结果</th></tr>\n");
+    for verdict in verdicts {
+        let observed = verdict
+            .observed
+            .map(|value| verdict.threshold.format_value(value))
+            .unwrap_or_else(|| "-".to_string());
+        let (class, outcome) = if verdict.passed {
+            ("pass", "通过")
+        } else {
+            ("fail", "失败")
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>",
+            escape(&verdict.threshold.to_string()),
+            observed,
+            class,
+            outcome
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_time_series(html: &mut String, time_series: &TimeSeries) {
+    if time_series.is_empty() {
+        return;
+    }
+    let buckets = &time_series.buckets;
+    let x = |second: u64| second as f64;
+    let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+
+    html.push_str("<h2>时间序列</h2>\n");
+    html.push_str(&line_chart(
+        "吞吐（请求/秒）",
+        "",
+        &[Series {
+            name: "RPS",
+            color: "#1f77b4",
+            points: buckets
+                .iter()
+                .map(|b| (x(b.second), b.requests as f64))
+                .collect(),
+        }],
+    ));
+    html.push_str(&line_chart(
+        "带宽（KB/秒）",
+        "",
+        &[Series {
+            name: "响应体",
+            color: "#17becf",
+            points: buckets
+                .iter()
+                .map(|b| (x(b.second), b.bytes as f64 / 1024.0))
+                .collect(),
+        }],
+    ));
+    html.push_str(&line_chart(
+        "错误率",
+        "%",
+        &[Series {
+            name: "错误率",
+            color: "#d62728",
+            points: buckets
+                .iter()
+                .map(|b| (x(b.second), b.error_rate() * 100.0))
+                .collect(),
+        }],
+    ));
+
+    let kinds = time_series.error_kinds();
+    if !kinds.is_empty() {
+        let series: Vec<Series> = kinds
+            .iter()
+            .zip(PALETTE.iter().cycle())
+            .map(|(&kind, &color)| Series {
+                name: kind,
+                color,
+                points: buckets
+                    .iter()
+                    .map(|b| {
+                        let count = b.errors_by_kind.get(kind).copied().unwrap_or(0);
+                        (x(b.second), count as f64)
+                    })
+                    .collect(),
+            })
+            .collect();
+        html.push_str(&line_chart("错误类别（次/秒）", "", &series));
+    }
+
+    let percentile = |p: f64| -> Vec<(f64, f64)> {
+        buckets
+            .iter()
+            .filter(|b| !b.latency.is_empty())
+            .map(|b| (x(b.second), millis(b.latency.value_at_percentile(p))))
+            .collect()
+    };
+    html.push_str(&line_chart(
+        "延迟分位",
+        "ms",
+        &[
+            Series {
+                name: "P50",
+                color: "#2ca02c",
+                points: percentile(50.0),
+            },
+            Series {
+                name: "P90",
+                color: "#ff7f0e",
+                points: percentile(90.0),
+            },
+            Series {
+                name: "P99",
+                color: "#9467bd",
+                points: percentile(99.0),
+            },
+        ],
+    ));
+}
+
+/// 以对数间隔分组的延迟分布柱状图
+fn render_histogram(html: &mut String, result: &LoadTestResult) {
+    let histogram = &result.latency_histogram;
+    if histogram.is_empty() {
+        return;
+    }
+
+    let low = histogram.min().as_secs_f64().max(1e-6);
+    let high = histogram.max().as_secs_f64().max(low);
+    let span = (high / low).ln();
+    let mut bins = vec![0u64; HISTOGRAM_BINS];
+    for (value, count) in histogram.iter_recorded() {
+        let value = value.as_secs_f64().max(low);
+        let index = if span > 0.0 {
+            ((value / low).ln() / span * HISTOGRAM_BINS as f64) as usize
+        } else {
+            0
+        };
+        bins[index.min(HISTOGRAM_BINS - 1)] += count;
+    }
+    let upper_edge = |index: usize| {
+        Duration::from_secs_f64(low * (span * (index + 1) as f64 / HISTOGRAM_BINS as f64).exp())
+    };
+
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let max_count = bins.iter().copied().max().unwrap_or(1).max(1) as f64;
+    let bar_width = plot_width / HISTOGRAM_BINS as f64;
+
+    let mut svg = chart_frame("延迟分布（请求数）", max_count, "");
+    for (index, &count) in bins.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let height = count as f64 / max_count * plot_height;
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#1f77b4\">\
+             <title>≤ {}: {}</title></rect>",
+            MARGIN_LEFT + index as f64 * bar_width + 1.0,
+            MARGIN_TOP + plot_height - height,
+            (bar_width - 2.0).max(1.0),
+            height,
+            format_duration(upper_edge(index)),
+            count
+        );
+    }
+    for index in (0..HISTOGRAM_BINS).step_by(HISTOGRAM_BINS / 5) {
+        let _ = write!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            MARGIN_LEFT + (index as f64 + 0.5) * bar_width,
+            CHART_HEIGHT - 10.0,
+            format_duration(upper_edge(index))
+        );
+    }
+    svg.push_str("</svg>\n");
+    html.push_str("<h2>延迟分布</h2>\n");
+    html.push_str(&svg);
+}
+
+fn render_status_codes(html: &mut String, result: &LoadTestResult) {
+    if result.total_requests == 0 {
+        return;
+    }
+    html.push_str(
+        "<h2>状态码</h2>\n<table>\n<tr><th>状态码</th><th>响应数</th><th>占比</th></tr>\n",
+    );
+    let responded: u64 = result.status_codes.values().sum();
+    let rows = result
+        .status_codes
+        .iter()
+        .map(|(status, count)| (status.to_string(), *count))
+        .chain(
+            (responded < result.total_requests)
+                .then(|| ("无响应".to_string(), result.total_requests - responded)),
+        );
+    for (status, count) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{:.2}%</td></tr>",
+            status,
+            count,
+            count as f64 / result.total_requests as f64 * 100.0
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_requests(html: &mut String, result: &LoadTestResult) {
+    let requests: Vec<(&str, &LoadTestResult)> = result
+        .request_results
+        .iter()
+        .map(|request| (request.name.as_str(), &request.result))
+        .collect();
+    result_table(html, "请求", &requests);
+
+    let stages: Vec<(&str, &LoadTestResult)> = result
+        .stage_results
+        .iter()
+        .map(|stage| (stage.name.as_str(), &stage.result))
+        .collect();
+    result_table(html, "阶段", &stages);
+
+    if !result.checks.is_empty() {
+        html.push_str(
+            "<h2>响应检查</h2>\n<table>\n<tr><th>请求</th><th>检查</th><th>通过</th>\
+             <th>失败</th><th>通过率</th></tr>\n",
+        );
+        for check in &result.checks {
+            let class = if check.failed == 0 { "pass" } else { "fail" };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{:.2}%</td></tr>",
+                escape(&check.request),
+                escape(&check.name),
+                check.passed,
+                check.failed,
+                class,
+                check.pass_rate() * 100.0
+            );
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+/// 按请求或阶段拆分的结果表
+fn result_table(html: &mut String, title: &str, rows: &[(&str, &LoadTestResult)]) {
+    if rows.is_empty() {
+        return;
+    }
+    let _ = write!(
+        html,
+        "<h2>{}结果</h2>\n<table>\n<tr><th>{}</th><th>请求数</th><th>成功率</th>\
+         <th>平均</th><th>P50</th><th>P95</th><th>P99</th><th>最大</th></tr>\n",
+        title, title
+    );
+    for (name, result) in rows {
+        let success_rate = if result.total_requests > 0 {
+            result.successful_requests as f64 / result.total_requests as f64 * 100.0
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{:.2}%</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td><td>{}</td></tr>",
+            escape(name),
+            result.total_requests,
+            success_rate,
+            format_duration(result.avg_latency),
+            format_duration(result.p50_latency),
+            format_duration(result.p95_latency),
+            format_duration(result.p99_latency),
+            format_duration(result.max_latency),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_config(html: &mut String, config: &LoadTestConfig) {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    if config.requests.is_empty() {
+        rows.push((
+            "目标".to_string(),
+            format!("{} {}", config.method, config.url),
+        ));
+    }
+    for template in &config.requests {
+        let weight = template
+            .weight
+            .map(|weight| format!(" (权重 {})", weight))
+            .unwrap_or_default();
+        rows.push((
+            format!("请求 {}", template.name),
+            format!(
+                "{} {}{}",
+                template.request.method, template.request.url, weight
+            ),
+        ));
+    }
+
+    if !config.stages.is_empty() {
+        for (index, stage) in config.stages.iter().enumerate() {
+            rows.push((
+                format!("阶段 {}", stage.display_name(index)),
+                format!("{} 秒 → {}", stage.duration_secs, stage.target),
+            ));
+        }
+    } else if let Some(rate) = config.arrival_rate {
+        rows.push(("到达速率".to_string(), format!("{}/s", rate)));
+        rows.push(("最大在途".to_string(), config.max_in_flight.to_string()));
+        rows.push(("时长".to_string(), format!("{} 秒", config.duration_secs)));
+    } else {
+        rows.push(("并发用户".to_string(), config.concurrent_users.to_string()));
+        rows.push(("时长".to_string(), format!("{} 秒", config.duration_secs)));
+    }
+    rows.push(("预热".to_string(), format!("{} 秒", config.warmup_secs)));
+    if config.stages.is_empty() {
+        rows.push(("爬坡".to_string(), format!("{} 秒", config.ramp_up_secs)));
+    }
+    for feeder in &config.feeders {
+        rows.push((
+            format!("数据源 {}", feeder.name()),
+            format!(
+                "{} 行, 列 [{}], {:?}",
+                feeder.len(),
+                feeder.columns().join(", "),
+                feeder.strategy()
+            ),
+        ));
+    }
+    for threshold in &config.thresholds {
+        rows.push(("阈值".to_string(), threshold.to_string()));
+    }
+
+    html.push_str("<h2>运行配置</h2>\n<table>\n");
+    for (key, value) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td style=\"text-align:left\">{}</td></tr>",
+            escape(&key),
+            escape(&value)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// 画出坐标轴、横向网格线和纵轴刻度，返回未闭合的 `<svg>`
+fn chart_frame(title: &str, y_max: f64, unit: &str) -> String {
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg viewBox=\"0 0 {w} {h}\" width=\"{w}\" height=\"{h}\" xmlns=\"http://www.w3.org/2000/svg\">\
+         <text x=\"{x}\" y=\"18\" style=\"font-size:13px;font-weight:600;fill:#222\">{title}</text>",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT,
+        x = MARGIN_LEFT,
+        title = escape(title)
+    );
+    for step in 0..=4 {
+        let y = MARGIN_TOP + plot_height * (1.0 - step as f64 / 4.0);
+        let _ = write!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#eee\"/>\
+             <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}{}</text>",
+            MARGIN_LEFT,
+            y,
+            MARGIN_LEFT + plot_width,
+            y,
+            MARGIN_LEFT - 6.0,
+            y + 4.0,
+            format_number(y_max * step as f64 / 4.0),
+            unit
+        );
+    }
+    svg
+}
+
+/// 以秒为横轴的折线图
+fn line_chart(title: &str, unit: &str, series: &[Series]) -> String {
+    let points = series.iter().flat_map(|s| s.points.iter());
+    let (x_min, x_max, y_max) = points.fold(
+        (f64::MAX, f64::MIN, 0.0f64),
+        |(x_min, x_max, y_max), &(x, y)| (x_min.min(x), x_max.max(x), y_max.max(y)),
+    );
+    if x_min > x_max {
+        return String::new();
+    }
+    let y_max = if y_max > 0.0 { y_max * 1.1 } else { 1.0 };
+    let x_span = (x_max - x_min).max(1.0);
+
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let to_x = |x: f64| MARGIN_LEFT + (x - x_min) / x_span * plot_width;
+    let to_y = |y: f64| MARGIN_TOP + plot_height * (1.0 - y / y_max);
+
+    let mut svg = chart_frame(title, y_max, unit);
+    for step in 0..=5 {
+        let x = x_min + x_span * step as f64 / 5.0;
+        let _ = write!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{:.0}s</text>",
+            to_x(x),
+            CHART_HEIGHT - 10.0,
+            x
+        );
+    }
+
+    let mut legend_x = CHART_WIDTH - MARGIN_RIGHT;
+    for s in series.iter().rev() {
+        legend_x -= 14.0 + 8.0 * s.name.chars().count() as f64 + 12.0;
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"10\" width=\"10\" height=\"10\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"19\">{}</text>",
+            legend_x,
+            s.color,
+            legend_x + 14.0,
+            escape(s.name)
+        );
+
+        let polyline: Vec<String> = s
+            .points
+            .iter()
+            .map(|&(x, y)| format!("{:.1},{:.1}", to_x(x), to_y(y)))
+            .collect();
+        let _ = write!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>",
+            s.color,
+            polyline.join(" ")
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// 纵轴刻度：大数用整数，小数保留两位
+fn format_number(value: f64) -> String {
+    if value >= 100.0 {
+        format!("{:.0}", value)
+    } else if value >= 10.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{CheckResult, RequestResult};
+
+    fn result() -> LoadTestResult {
+        let mut result = LoadTestResult::default();
+        for (i, ms) in [10u64, 20, 30, 400].into_iter().enumerate() {
+            let latency = Duration::from_millis(ms);
+            let error = (ms > 100).then_some("timeout");
+            result.add_response(
+                if error.is_some() { 0 } else { 200 },
+                latency,
+                error.is_none(),
+            );
+            result.time_series.record(
+                Duration::from_millis(600 * i as u64),
+                Some(latency),
+                16,
+                error,
+            );
+        }
+        result.elapsed = Duration::from_secs(3);
+        result.calculate_qps();
+        result.calculate_percentiles();
+        result.request_results.push(RequestResult {
+            name: "<GET & list>".to_string(),
+            share: None,
+            result: result.clone(),
+        });
+        let mut check = CheckResult::new("<GET & list>", "body contains \"<ok>\"");
+        check.record(false);
+        result.checks.push(check);
+        result.aborted = Some("阈值 p99 < 100ms 已无法满足".to_string());
+        result
+    }
+
+    #[test]
+    fn renders_a_self_contained_report_with_escaped_text() {
+        let config = LoadTestConfig {
+            thresholds: vec!["p99 < 100ms".parse().unwrap()],
+            ..LoadTestConfig::default()
+        };
+        let html = render(&config, &result());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</main>\n</body>\n</html>\n"));
+        // 不引用外部资源
+        for external in ["<script", "<link", "src=", "@import", "url("] {
+            assert!(!html.contains(external), "{external}");
+        }
+        for section in [
+            "摘要",
+            "状态码",
+            "阈值",
+            "时间序列",
+            "延迟分布",
+            "请求结果",
+            "响应检查",
+            "运行配置",
+        ] {
+            assert!(html.contains(&format!("<h2>{section}</h2>")), "{section}");
+        }
+        assert_eq!(html.matches("<svg").count(), html.matches("</svg>").count());
+        assert!(html.matches("<svg").count() >= 4);
+        assert!(html.contains("压测已提前结束: 阈值 p99 &lt; 100ms 已无法满足"));
+        assert!(html.contains("<td>&lt;GET &amp; list&gt;</td>"));
+        assert!(html.contains("<td>body contains &quot;&lt;ok&gt;&quot;</td>"));
+        assert!(!html.contains("<GET"));
+        assert!(html.contains("<td class=\"fail\">失败</td>"));
+    }
+
+    #[test]
+    fn skips_sections_without_data() {
+        let html = render(&LoadTestConfig::default(), &LoadTestResult::default());
+        for section in ["阈值", "时间序列", "请求结果", "阶段结果", "响应检查"] {
+            assert!(!html.contains(&format!("<h2>{section}</h2>")), "{section}");
+        }
+        assert!(html.contains("<h2>摘要</h2>"));
+        assert!(!html.contains("NaN") && !html.contains("inf"));
+    }
+}
@@ -1,82 +1,148 @@
+use super::header_text;
+use super::timing::{self, ConnectPhase, TimedConnector, TimedResolver};
 use crate::domain::entities::{HttpRequest, HttpResponse};
-use crate::domain::services::{HttpClient, HttpClientError};
+use crate::domain::services::{HttpClient, HttpClientError, RequestOptions};
 use crate::domain::value_objects::{Body as DomainBody, ContentType, Headers};
-use std::time::Instant;
+use crate::optimization::{HttpVersion, PerformanceConfig};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::{TokioExecutor, TokioTimer};
+use std::time::{Duration, Instant};
 
+/// 逐层计时的连接器：DNS → TCP → TLS
+type TimedHttpsConnector =
+    TimedConnector<HttpsConnector<TimedConnector<HttpConnector<TimedResolver>>>>;
+
+/// 基于 hyper 1.x 连接池的高性能客户端，用于压测
+///
+/// 与 `ReqwestClient` 不同，它不跟随重定向，3xx 响应原样返回。
 #[derive(Clone)]
 pub struct HyperClient {
-    client: reqwest::Client,
-    timeout: std::time::Duration,
+    client: Client<TimedHttpsConnector, Full<Bytes>>,
+    timeout: Duration,
 }
 
 impl HyperClient {
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .pool_max_idle_per_host(1000)
-            .timeout(std::time::Duration::from_secs(30))
+    pub fn new() -> Result<Self, HttpClientError> {
+        Self::with_config(&PerformanceConfig::default(), &RequestOptions::default())
+    }
+
+    /// 按性能配置（连接池、协议版本）和请求选项（超时、证书校验、连接复用）创建客户端
+    pub fn with_config(
+        config: &PerformanceConfig,
+        options: &RequestOptions,
+    ) -> Result<Self, HttpClientError> {
+        let mut http = HttpConnector::new_with_resolver(TimedResolver::new());
+        http.enforce_http(false);
+        http.set_nodelay(config.tcp_nodelay);
+        http.set_connect_timeout(Some(Duration::from_secs(config.connect_timeout_secs)));
+
+        let alpn: &[&str] = match config.http_version {
+            HttpVersion::Http1 => &["http/1.1"],
+            HttpVersion::Http2 => &["h2"],
+        };
+        let tls = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(!options.verify_ssl)
+            .request_alpns(alpn)
             .build()
-            .expect("Failed to create reqwest client");
+            .map_err(|e| HttpClientError::SslError(e.to_string()))?;
+        let http = TimedConnector::new(http, ConnectPhase::Tcp);
+        let connector = TimedConnector::new(
+            HttpsConnector::from((http, tokio_native_tls::TlsConnector::from(tls))),
+            ConnectPhase::Established,
+        );
 
-        Self {
+        let max_idle_per_host = if options.reuse_connections {
+            config.max_idle_per_host
+        } else {
+            0
+        };
+
+        let client = Client::builder(TokioExecutor::new())
+            .pool_timer(TokioTimer::new())
+            .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+            .pool_max_idle_per_host(max_idle_per_host)
+            .http2_only(config.http_version == HttpVersion::Http2)
+            .http2_initial_max_send_streams(config.http2_max_concurrent_streams as usize)
+            .build(connector);
+
+        Ok(Self {
             client,
-            timeout: std::time::Duration::from_secs(30),
-        }
+            timeout: Duration::from_secs(options.timeout_seconds),
+        })
     }
 
     async fn execute_inner(&self, request: &HttpRequest) -> Result<HttpResponse, HttpClientError> {
         let start_time = Instant::now();
 
         // 构建请求
-        let mut req_builder = self.client
-            .request(request.method.as_reqwest_method(), request.url.as_str());
+        let mut builder = http::Request::builder()
+            .method(request.method.as_reqwest_method())
+            .uri(request.url.as_str());
 
         // 添加headers
         for (key, value) in request.headers.iter() {
-            req_builder = req_builder.header(key, value);
+            builder = builder.header(key.as_str(), value.as_str());
         }
 
         // 添加body
-        if let Some(body) = &request.body {
-            req_builder = req_builder.body(body.to_string());
-        }
+        let body = match &request.body {
+            Some(body) => Full::new(body.bytes()),
+            None => Full::new(Bytes::new()),
+        };
+        let http_request = builder
+            .body(body)
+            .map_err(|e| HttpClientError::InvalidUrl(e.to_string()))?;
 
-        // 发送请求
-        let response = req_builder
-            .send()
+        // 发送请求并读取完整响应体
+        let timeout = request.timeout.min(self.timeout);
+        let ((parts, body_bytes, headers_elapsed), connect_timings) =
+            tokio::time::timeout(timeout, async {
+                let (response, connect_timings) =
+                    timing::capture(self.client.request(http_request)).await;
+                let headers_elapsed = start_time.elapsed();
+                let (parts, body) = response?.into_parts();
+                // 与 reqwest 一致，读取响应体时出错（如连接提前关闭）计为响应体错误
+                let bytes = body
+                    .collect()
+                    .await
+                    .map_err(|e| HttpClientError::SerializationError(e.to_string()))?
+                    .to_bytes();
+                Ok::<_, HttpClientError>(((parts, bytes, headers_elapsed), connect_timings))
+            })
             .await
-            .map_err(|e| HttpClientError::ConnectionFailed(e.to_string()))?;
+            .map_err(|_| HttpClientError::Timeout)??;
 
         let duration = start_time.elapsed();
-        let status = response.status().as_u16();
+        let is_tls = request.url.as_str().starts_with("https://");
+        let timing = connect_timings.into_request_timing(
+            is_tls,
+            headers_elapsed,
+            duration.saturating_sub(headers_elapsed),
+        );
 
         // 处理headers
-        let mut headers_map = std::collections::HashMap::new();
-        for (key, value) in response.headers() {
-            headers_map.insert(key.to_string(), value.to_str().unwrap_or("").to_string());
+        let mut headers = Headers::with_capacity(parts.headers.keys_len());
+        for (key, value) in parts.headers.iter() {
+            headers.append(key.as_str(), header_text(value));
         }
 
         // 处理body
-        let body_bytes = response
-            .bytes()
-            .await
-            .map_err(|e| HttpClientError::IoError(e.to_string()))?;
-
-        let body_str = String::from_utf8_lossy(&body_bytes).to_string();
-        let body = if body_str.is_empty() {
-            None
-        } else {
-            Some(DomainBody::from_string(body_str, ContentType::Text))
-        };
-
-        let timestamp = chrono::DateTime::from_timestamp(chrono::Utc::now().timestamp(), 0)
-            .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
+        let body_size = body_bytes.len() as u64;
+        let body =
+            (!body_bytes.is_empty()).then(|| DomainBody::from_bytes(body_bytes, ContentType::Text));
 
         Ok(HttpResponse {
-            status,
-            headers: Headers::from(headers_map),
+            status: parts.status.as_u16(),
+            headers,
             body,
+            body_size,
             duration,
-            timestamp,
+            timestamp: chrono::Utc::now(),
+            timing,
         })
     }
 }
@@ -94,6 +160,120 @@ impl HttpClient for HyperClient {
 
 impl Default for HyperClient {
     fn default() -> Self {
-        Self::new()
+        Self::new().expect("Failed to create hyper client")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::Url;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// 在本地端口上用 `handle` 处理每个连接，返回端口
+    async fn serve<F, Fut>(handle: F) -> u16
+    where
+        F: Fn(TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle(stream));
+            }
+        });
+        port
+    }
+
+    /// 读完请求头后写出 `response` 并关闭连接
+    async fn respond(mut stream: TcpStream, response: &'static str) {
+        let mut buffer = [0u8; 4096];
+        let _ = stream.read(&mut buffer).await;
+        let _ = stream.write_all(response.as_bytes()).await;
+    }
+
+    async fn get(url: String, timeout: Duration) -> Result<HttpResponse, HttpClientError> {
+        let request = HttpRequest {
+            url: Url::parse(&url).unwrap(),
+            timeout,
+            ..HttpRequest::default()
+        };
+        HyperClient::new().unwrap().execute(&request).await
+    }
+
+    #[tokio::test]
+    async fn classifies_handshake_failures_as_tls_errors() {
+        let port = serve(|stream| respond(stream, "HTTP/1.1 400 Bad Request\r\n\r\n")).await;
+        let error = get(format!("https://127.0.0.1:{port}/"), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, HttpClientError::SslError(_)), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn classifies_refused_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let error = get(format!("http://127.0.0.1:{port}/"), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, HttpClientError::ConnectionFailed(_)),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn classifies_connections_closed_before_the_response() {
+        let port = serve(|stream| respond(stream, "")).await;
+        let error = get(format!("http://127.0.0.1:{port}/"), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, HttpClientError::ConnectionFailed(_)),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn classifies_malformed_and_truncated_responses_as_body_errors() {
+        let port = serve(|stream| respond(stream, "not http\r\n\r\n")).await;
+        let error = get(format!("http://127.0.0.1:{port}/"), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, HttpClientError::SerializationError(_)),
+            "{error:?}"
+        );
+
+        let truncated = "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nab";
+        let port = serve(move |stream| respond(stream, truncated)).await;
+        let error = get(format!("http://127.0.0.1:{port}/"), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, HttpClientError::SerializationError(_)),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn classifies_slow_responses_as_timeouts() {
+        let port = serve(|mut stream| async move {
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer).await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        })
+        .await;
+        let error = get(
+            format!("http://127.0.0.1:{port}/"),
+            Duration::from_millis(200),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, HttpClientError::Timeout), "{error:?}");
     }
 }
@@ -1,5 +1,52 @@
 mod hyper_client;
 mod reqwest_client;
+mod timing;
 
 pub use hyper_client::HyperClient;
 pub use reqwest_client::ReqwestClient;
+
+use crate::domain::services::{HttpClient, HttpClientError, RequestOptions};
+use crate::optimization::PerformanceConfig;
+use std::borrow::Cow;
+use std::str::FromStr;
+
+/// 压测使用的 HTTP 客户端实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClientBackend {
+    /// reqwest：功能完整，跟随重定向
+    #[default]
+    Reqwest,
+    /// hyper：连接池可调，面向高吞吐压测
+    Hyper,
+}
+
+impl ClientBackend {
+    /// 创建对应实现的客户端
+    pub fn create(
+        self,
+        options: &RequestOptions,
+        performance: &PerformanceConfig,
+    ) -> Result<Box<dyn HttpClient>, HttpClientError> {
+        Ok(match self {
+            Self::Reqwest => Box::new(ReqwestClient::with_options(options)?),
+            Self::Hyper => Box::new(HyperClient::with_config(performance, options)?),
+        })
+    }
+}
+
+impl FromStr for ClientBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reqwest" => Ok(Self::Reqwest),
+            "hyper" => Ok(Self::Hyper),
+            _ => Err(format!("Invalid client backend: {}", s)),
+        }
+    }
+}
+
+/// 响应头的值按 UTF-8 解码，无效的字节替换为 U+FFFD，不丢弃整个值
+fn header_text(value: &http::HeaderValue) -> Cow<'_, str> {
+    String::from_utf8_lossy(value.as_bytes())
+}
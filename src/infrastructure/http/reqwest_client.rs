@@ -1,7 +1,9 @@
+use super::header_text;
+use super::timing::{self, TimedConnectorLayer, TimedResolver};
 use crate::domain::entities::{HttpRequest, HttpResponse};
-use crate::domain::services::{HttpClient, HttpClientError};
+use crate::domain::services::{HttpClient, HttpClientError, RequestOptions};
 use crate::domain::value_objects::{Body, ContentType, Headers};
-use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 #[derive(Clone)]
@@ -11,11 +13,33 @@ pub struct ReqwestClient {
 
 impl ReqwestClient {
     pub fn new() -> Result<Self, HttpClientError> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        Self::with_options(&RequestOptions::default())
+    }
+
+    /// 按请求选项创建客户端
+    ///
+    /// reqwest 不单独暴露 TLS 握手，HTTPS 建连时握手耗时计入 TCP 建连。
+    pub fn with_options(options: &RequestOptions) -> Result<Self, HttpClientError> {
+        let redirect_policy = if options.follow_redirects {
+            reqwest::redirect::Policy::limited(options.redirect_limit as usize)
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(options.timeout_seconds))
+            .redirect(redirect_policy)
+            .danger_accept_invalid_certs(!options.verify_ssl)
+            .dns_resolver(Arc::new(TimedResolver::new()))
+            .connector_layer(TimedConnectorLayer);
 
-        Ok(Self { client })
+        if !options.reuse_connections {
+            builder = builder.pool_max_idle_per_host(0);
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+        })
     }
 }
 
@@ -41,36 +65,39 @@ impl HttpClient for ReqwestClient {
         }
 
         if let Some(body) = &request.body {
-            req_builder = req_builder.body(body.to_string());
+            req_builder = req_builder.body(body.bytes());
         }
 
         let request = req_builder
             .build()
             .map_err(|e| HttpClientError::SerializationError(e.to_string()))?;
-        let response = self.client.execute(request).await?;
+        let (response, connect_timings) = timing::capture(self.client.execute(request)).await;
+        let response = response?;
         let duration = start_time.elapsed();
         let status = response.status().as_u16();
 
-        let mut headers_map: HashMap<String, String> = HashMap::new();
+        let mut headers = Headers::with_capacity(response.headers().keys_len());
         for (k, v) in response.headers().iter() {
-            headers_map.insert(k.to_string(), v.to_str().unwrap_or("").to_string());
+            headers.append(k.as_str(), header_text(v));
         }
 
-        let body = response
-            .text()
-            .await
-            .ok()
-            .map(|s| Body::from_string(s, ContentType::Text));
+        let body_bytes = response.bytes().await.ok();
+        let body_size = body_bytes.as_ref().map_or(0, |bytes| bytes.len() as u64);
+        let body = body_bytes.map(|bytes| Body::from_bytes(bytes, ContentType::Text));
+        let timing =
+            connect_timings.into_request_timing(false, duration, start_time.elapsed() - duration);
 
         let timestamp = chrono::DateTime::from_timestamp(chrono::Utc::now().timestamp(), 0)
             .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
 
         Ok(HttpResponse {
             status,
-            headers: Headers::from(headers_map),
+            headers,
             body,
+            body_size,
             duration,
             timestamp,
+            timing,
         })
     }
 
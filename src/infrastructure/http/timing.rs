@@ -0,0 +1,202 @@
+//! 连接阶段计时
+//!
+//! DNS 解析和建连发生在客户端连接池内部，无法直接从请求外部观测。
+//! 这里用包装过的解析器和连接器把各阶段耗时写入当前任务的记录器；
+//! 复用连接或在后台任务中完成的建连不会留下记录。
+
+use crate::domain::entities::RequestTiming;
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use std::cell::Cell;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower_service::Service;
+
+/// 单次请求中观测到的建连耗时，均从各自阶段开始时计起
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ConnectTimings {
+    /// DNS 解析
+    pub dns: Option<Duration>,
+    /// 建立 TCP 连接（含 DNS）
+    pub tcp: Option<Duration>,
+    /// 建立可用连接（含 DNS、TCP 和 TLS）
+    pub established: Option<Duration>,
+}
+
+impl ConnectTimings {
+    /// 建连总耗时，复用连接时为 0
+    pub fn setup(&self) -> Duration {
+        self.established
+            .or(self.tcp)
+            .or(self.dns)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 拆分为各阶段耗时
+    ///
+    /// `headers` 为发出请求到收到响应头的总耗时；未单独测量 TCP 时，
+    /// TLS 握手计入 `connect`。
+    pub fn into_request_timing(
+        self,
+        is_tls: bool,
+        headers: Duration,
+        transfer: Duration,
+    ) -> RequestTiming {
+        let dns = self.dns.unwrap_or(Duration::ZERO);
+        let (connect, tls) = match (self.tcp, self.established) {
+            (Some(tcp), established) => (
+                Some(tcp.saturating_sub(dns)),
+                established
+                    .filter(|_| is_tls)
+                    .map(|established| established.saturating_sub(tcp)),
+            ),
+            (None, Some(established)) => (Some(established.saturating_sub(dns)), None),
+            (None, None) => (None, None),
+        };
+
+        RequestTiming {
+            dns: self.dns,
+            connect,
+            tls,
+            ttfb: headers.saturating_sub(self.setup()),
+            transfer,
+        }
+    }
+}
+
+tokio::task_local! {
+    static CONNECT_TIMINGS: Cell<ConnectTimings>;
+}
+
+/// 在计时作用域内执行 `future`，返回结果和观测到的建连耗时
+pub(crate) async fn capture<F: Future>(future: F) -> (F::Output, ConnectTimings) {
+    CONNECT_TIMINGS
+        .scope(Cell::new(ConnectTimings::default()), async {
+            let output = future.await;
+            (output, CONNECT_TIMINGS.with(Cell::get))
+        })
+        .await
+}
+
+fn record(update: impl FnOnce(&mut ConnectTimings)) {
+    let _ = CONNECT_TIMINGS.try_with(|cell| {
+        let mut timings = cell.get();
+        update(&mut timings);
+        cell.set(timings);
+    });
+}
+
+/// 计时连接器包装所处的层级
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ConnectPhase {
+    Tcp,
+    Established,
+}
+
+/// 记录内部连接器耗时的包装
+#[derive(Debug, Clone)]
+pub(crate) struct TimedConnector<S> {
+    inner: S,
+    phase: ConnectPhase,
+}
+
+impl<S> TimedConnector<S> {
+    pub fn new(inner: S, phase: ConnectPhase) -> Self {
+        Self { inner, phase }
+    }
+}
+
+impl<S, R> Service<R> for TimedConnector<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let phase = self.phase;
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = connecting.await;
+            let elapsed = start.elapsed();
+            record(|timings| match phase {
+                ConnectPhase::Tcp => timings.tcp = Some(elapsed),
+                ConnectPhase::Established => timings.established = Some(elapsed),
+            });
+            result
+        })
+    }
+}
+
+/// 为 reqwest 的连接器套上计时包装
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TimedConnectorLayer;
+
+impl<S> tower_layer::Layer<S> for TimedConnectorLayer {
+    type Service = TimedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnector::new(inner, ConnectPhase::Established)
+    }
+}
+
+/// 记录 DNS 解析耗时的解析器，供 hyper 和 reqwest 共用
+#[derive(Debug, Clone)]
+pub(crate) struct TimedResolver {
+    inner: GaiResolver,
+}
+
+impl TimedResolver {
+    pub fn new() -> Self {
+        Self {
+            inner: GaiResolver::new(),
+        }
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type Addrs = Box<dyn Iterator<Item = SocketAddr> + Send>;
+
+impl Service<Name> for TimedResolver {
+    type Response = Addrs;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Addrs, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let resolving = self.inner.call(name);
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs = resolving.await?;
+            let elapsed = start.elapsed();
+            record(|timings| timings.dns = Some(elapsed));
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+impl reqwest::dns::Resolve for TimedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let elapsed = start.elapsed();
+            record(|timings| timings.dns = Some(elapsed));
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
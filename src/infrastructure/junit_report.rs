@@ -0,0 +1,162 @@
+//! JUnit XML 报告
+//!
+//! 每个阈值和每个响应检查各是一个测试用例，CI 可以把压测结果和单元测试放在一起展示。
+
+use super::export::escape_markup as escape;
+use crate::domain::entities::{LoadTestConfig, LoadTestResult, evaluate_thresholds};
+use std::fmt::Write;
+
+/// 单个测试用例，`failure` 为失败说明
+struct TestCase {
+    classname: String,
+    name: String,
+    failure: Option<String>,
+}
+
+/// 生成 JUnit XML：`thresholds` 和 `checks` 两个测试套件
+pub fn render(config: &LoadTestConfig, result: &LoadTestResult) -> String {
+    let thresholds: Vec<TestCase> = evaluate_thresholds(&config.thresholds, result)
+        .into_iter()
+        .map(|verdict| {
+            let observed = verdict
+                .observed
+                .map(|value| verdict.threshold.format_value(value))
+                .unwrap_or_else(|| "无请求".to_string());
+            TestCase {
+                classname: "bolt.thresholds".to_string(),
+                name: verdict.threshold.to_string(),
+                failure: (!verdict.passed)
+                    .then(|| format!("{} 未满足，实际值 {}", verdict.threshold, observed)),
+            }
+        })
+        .collect();
+
+    let checks: Vec<TestCase> = result
+        .checks
+        .iter()
+        .map(|check| TestCase {
+            classname: format!("bolt.checks.{}", check.request),
+            name: check.name.clone(),
+            failure: (check.failed > 0).then(|| {
+                format!(
+                    "{} 次中失败 {} 次，通过率 {:.2}%",
+                    check.total(),
+                    check.failed,
+                    check.pass_rate() * 100.0
+                )
+            }),
+        })
+        .collect();
+
+    let suites = [("thresholds", thresholds), ("checks", checks)];
+    let tests: usize = suites.iter().map(|(_, cases)| cases.len()).sum();
+    let failures = count_failures(suites.iter().flat_map(|(_, cases)| cases));
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"bolt\" tests=\"{}\" failures=\"{}\">",
+        tests, failures
+    );
+    for (name, cases) in &suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            name,
+            cases.len(),
+            count_failures(cases.iter())
+        );
+        for case in cases {
+            let _ = write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape(&case.classname),
+                escape(&case.name)
+            );
+            match &case.failure {
+                Some(message) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"{}\" type=\"{}\"/>\n    </testcase>",
+                        escape(message),
+                        name
+                    );
+                }
+                None => xml.push_str("/>\n"),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn count_failures<'a>(cases: impl Iterator<Item = &'a TestCase>) -> usize {
+    cases.filter(|case| case.failure.is_some()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::CheckResult;
+    use std::time::Duration;
+
+    fn result() -> LoadTestResult {
+        let mut result = LoadTestResult::default();
+        for ms in [10, 20, 30, 40] {
+            result.add_response(200, Duration::from_millis(ms), true);
+        }
+        result.calculate_percentiles();
+        let mut status = CheckResult::new("GET /users?a=1&b=2", "status in [200]");
+        status.record(true);
+        status.record(true);
+        let mut body = CheckResult::new("GET /users?a=1&b=2", r#"json $.name == "bob""#);
+        body.record(true);
+        body.record(false);
+        result.checks = vec![status, body];
+        result
+    }
+
+    #[test]
+    fn reports_thresholds_and_checks_as_test_cases() {
+        let config = LoadTestConfig {
+            thresholds: vec![
+                "p99 < 100ms".parse().unwrap(),
+                "max < 15ms".parse().unwrap(),
+            ],
+            ..LoadTestConfig::default()
+        };
+        let xml = render(&config, &result());
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml.contains("<testsuites name=\"bolt\" tests=\"4\" failures=\"2\">"));
+        assert!(xml.contains("<testsuite name=\"thresholds\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains("<testsuite name=\"checks\" tests=\"2\" failures=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"bolt.thresholds\" name=\"p99 &lt; 100ms\"/>"));
+        assert!(xml.contains("name=\"max &lt; 15ms\">\n      <failure message=\"max &lt; 15ms 未满足，实际值 40.00ms\" type=\"thresholds\"/>\n    </testcase>"));
+
+        // 请求名称和检查名称中的特殊字符被转义
+        let classname = "classname=\"bolt.checks.GET /users?a=1&amp;b=2\"";
+        assert!(xml.contains(&format!("{classname} name=\"status in [200]\"/>")));
+        assert!(xml.contains(&format!(
+            "{classname} name=\"json $.name == &quot;bob&quot;\">\n      <failure message=\"2 次中失败 1 次，通过率 50.00%\" type=\"checks\"/>"
+        )));
+        assert_eq!(xml.matches("<testcase ").count(), 4);
+        assert_eq!(xml.matches("</testcase>").count(), 2);
+        assert!(xml.ends_with("  </testsuite>\n</testsuites>\n"));
+    }
+
+    #[test]
+    fn fails_thresholds_without_requests() {
+        let config = LoadTestConfig {
+            thresholds: vec!["p99 < 100ms".parse().unwrap()],
+            ..LoadTestConfig::default()
+        };
+        let xml = render(&config, &LoadTestResult::default());
+        assert!(xml.contains("<testsuites name=\"bolt\" tests=\"1\" failures=\"1\">"));
+        assert!(xml.contains("实际值 无请求"));
+        assert!(
+            xml.contains("<testsuite name=\"checks\" tests=\"0\" failures=\"0\">\n  </testsuite>")
+        );
+    }
+}
@@ -1 +1,7 @@
+pub mod export;
+pub mod feeder;
+pub mod html_report;
 pub mod http;
+pub mod junit_report;
+pub mod prometheus;
+pub mod scenario;
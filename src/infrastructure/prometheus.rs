@@ -0,0 +1,401 @@
+//! Prometheus 指标端点
+//!
+//! 压测过程中在本地地址的 `/metrics` 上提供文本格式的实时指标，
+//! 按请求名、状态码和错误类别分组，可以直接叠加到被测服务的监控面板上。
+//! 预热请求带 `phase="warmup"` 标签，正式压测的请求为 `phase="test"`，
+//! 查询时按 `phase` 过滤即可排除预热。
+
+use crate::domain::entities::RequestRecord;
+use crate::domain::services::RequestLog;
+use crate::optimization::{LATENCY_BUCKETS_SECONDS, MetricLabels, MetricsRegistry};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::net::TcpListener;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 接受连接出错（例如文件描述符耗尽）后的首次等待，连续出错时翻倍
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(5);
+/// 接受连接出错后的最长等待
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error)]
+pub enum PrometheusError {
+    #[error("Failed to bind metrics endpoint {addr}: {source}")]
+    Bind {
+        addr: SocketAddr,
+        source: std::io::Error,
+    },
+}
+
+/// 接收每个请求的记录并通过 HTTP 端点提供给 Prometheus 抓取
+pub struct PrometheusExporter {
+    registry: Arc<MetricsRegistry>,
+    local_addr: SocketAddr,
+}
+
+impl PrometheusExporter {
+    /// 绑定地址并在后台开始服务，需要在 tokio 运行时中调用
+    pub fn bind(addr: SocketAddr) -> Result<Self, PrometheusError> {
+        let bind_error = |source| PrometheusError::Bind { addr, source };
+        let listener = std::net::TcpListener::bind(addr).map_err(bind_error)?;
+        listener.set_nonblocking(true).map_err(bind_error)?;
+        let local_addr = listener.local_addr().map_err(bind_error)?;
+        let listener = TcpListener::from_std(listener).map_err(bind_error)?;
+
+        let registry = Arc::new(MetricsRegistry::new());
+        tokio::spawn(serve(listener, registry.clone()));
+        Ok(Self {
+            registry,
+            local_addr,
+        })
+    }
+
+    /// 实际监听的地址，绑定端口 0 时可以从这里取到分配的端口
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn registry(&self) -> &Arc<MetricsRegistry> {
+        &self.registry
+    }
+}
+
+impl RequestLog for PrometheusExporter {
+    fn record(&self, record: RequestRecord) {
+        let labels = MetricLabels {
+            request: record.request,
+            status: record.status,
+            error: record.error,
+            warmup: record.warmup,
+        };
+        self.registry.record(
+            labels,
+            record.bytes_sent as usize,
+            record.bytes as usize,
+            record.latency,
+        );
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+async fn serve(listener: TcpListener, registry: Arc<MetricsRegistry>) {
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    loop {
+        // 出错时立即重试会在文件描述符耗尽时空转，抢占施压端的 CPU
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => {
+                backoff = ACCEPT_BACKOFF_MIN;
+                stream
+            }
+            Err(_) => {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                continue;
+            }
+        };
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request: Request<Incoming>| {
+                let response = respond(&request, &registry);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+fn respond(request: &Request<Incoming>, registry: &MetricsRegistry) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    if request.uri().path() != "/metrics" {
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return response;
+    }
+    *response.body_mut() = Full::new(Bytes::from(render(registry)));
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static(CONTENT_TYPE),
+    );
+    response
+}
+
+/// 生成 Prometheus 文本格式
+pub fn render(registry: &MetricsRegistry) -> String {
+    let series = registry.series();
+    let mut text = String::new();
+
+    text.push_str("# HELP bolt_requests_total Requests completed by the load generator.\n");
+    text.push_str("# TYPE bolt_requests_total counter\n");
+    for (labels, metrics) in &series {
+        let _ = writeln!(
+            text,
+            "bolt_requests_total{{{}}} {}",
+            format_labels(labels),
+            metrics.metrics.total_requests.load(Ordering::Relaxed)
+        );
+    }
+
+    text.push_str("# HELP bolt_request_bytes_total Request body bytes sent.\n");
+    text.push_str("# TYPE bolt_request_bytes_total counter\n");
+    for (labels, metrics) in &series {
+        let _ = writeln!(
+            text,
+            "bolt_request_bytes_total{{{}}} {}",
+            format_labels(labels),
+            metrics.metrics.total_bytes_sent.load(Ordering::Relaxed)
+        );
+    }
+
+    text.push_str("# HELP bolt_response_bytes_total Response body bytes received.\n");
+    text.push_str("# TYPE bolt_response_bytes_total counter\n");
+    for (labels, metrics) in &series {
+        let _ = writeln!(
+            text,
+            "bolt_response_bytes_total{{{}}} {}",
+            format_labels(labels),
+            metrics.metrics.total_bytes_received.load(Ordering::Relaxed)
+        );
+    }
+
+    text.push_str("# HELP bolt_request_duration_seconds Request latency.\n");
+    text.push_str("# TYPE bolt_request_duration_seconds histogram\n");
+    for (labels, metrics) in &series {
+        let labels = format_labels(labels);
+        for (upper, count) in LATENCY_BUCKETS_SECONDS
+            .iter()
+            .zip(metrics.cumulative_buckets())
+        {
+            let _ = writeln!(
+                text,
+                "bolt_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                labels, upper, count
+            );
+        }
+        let snapshot = metrics.metrics.snapshot();
+        let _ = writeln!(
+            text,
+            "bolt_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+            labels, snapshot.total_requests
+        );
+        let _ = writeln!(
+            text,
+            "bolt_request_duration_seconds_sum{{{}}} {}",
+            labels,
+            snapshot.total_response_time_ns as f64 / 1e9
+        );
+        let _ = writeln!(
+            text,
+            "bolt_request_duration_seconds_count{{{}}} {}",
+            labels, snapshot.total_requests
+        );
+    }
+    text
+}
+
+fn format_labels(labels: &MetricLabels) -> String {
+    format!(
+        "request=\"{}\",status=\"{}\",error=\"{}\",phase=\"{}\"",
+        escape_label(&labels.request),
+        labels.status,
+        escape_label(labels.error.as_deref().unwrap_or("")),
+        if labels.warmup { "warmup" } else { "test" }
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{LoadTestUseCase, LoadTestUseCaseImpl};
+    use crate::domain::entities::{HttpRequest, HttpResponse, LoadTestConfig, RequestTemplate};
+    use crate::domain::services::{HttpClient, HttpClientError};
+    use crate::domain::value_objects::{Body, Headers, Url};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn labels(request: &str, status: u16, error: Option<&str>, warmup: bool) -> MetricLabels {
+        MetricLabels {
+            request: request.to_string(),
+            status,
+            error: error.map(str::to_string),
+            warmup,
+        }
+    }
+
+    #[test]
+    fn renders_counters_and_histograms_with_escaped_labels() {
+        let registry = MetricsRegistry::new();
+        let request = "GET /search?q=\"a\\b\"\nnext";
+        let ok = labels(request, 200, None, false);
+        registry.record(ok.clone(), 10, 100, Duration::from_millis(3));
+        registry.record(ok, 10, 100, Duration::from_millis(30));
+        registry.record(
+            labels(request, 0, Some("timeout"), true),
+            10,
+            0,
+            Duration::from_secs(20),
+        );
+        let text = render(&registry);
+
+        let ok = r#"request="GET /search?q=\"a\\b\"\nnext",status="200",error="",phase="test""#;
+        let timeout =
+            r#"request="GET /search?q=\"a\\b\"\nnext",status="0",error="timeout",phase="warmup""#;
+        for line in [
+            format!("bolt_requests_total{{{ok}}} 2"),
+            format!("bolt_requests_total{{{timeout}}} 1"),
+            format!("bolt_request_bytes_total{{{ok}}} 20"),
+            format!("bolt_response_bytes_total{{{ok}}} 200"),
+            format!("bolt_response_bytes_total{{{timeout}}} 0"),
+            format!("bolt_request_duration_seconds_bucket{{{ok},le=\"0.005\"}} 1"),
+            format!("bolt_request_duration_seconds_bucket{{{ok},le=\"0.025\"}} 1"),
+            format!("bolt_request_duration_seconds_bucket{{{ok},le=\"0.05\"}} 2"),
+            format!("bolt_request_duration_seconds_bucket{{{ok},le=\"+Inf\"}} 2"),
+            format!("bolt_request_duration_seconds_sum{{{ok}}} 0.033"),
+            format!("bolt_request_duration_seconds_count{{{ok}}} 2"),
+            // 超出最大桶的请求只计入 +Inf
+            format!("bolt_request_duration_seconds_bucket{{{timeout},le=\"10\"}} 0"),
+            format!("bolt_request_duration_seconds_bucket{{{timeout},le=\"+Inf\"}} 1"),
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}\n{text}");
+        }
+        for metric in [
+            "bolt_requests_total counter",
+            "bolt_request_bytes_total counter",
+            "bolt_response_bytes_total counter",
+            "bolt_request_duration_seconds histogram",
+        ] {
+            assert!(text.contains(&format!("# TYPE {metric}\n")));
+        }
+        // 每行都是注释或 `名称{标签} 值`，标签值中的换行已转义
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            assert!(line.starts_with("bolt_") && line.contains("} "), "{line}");
+        }
+    }
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {path} HTTP/1.1\r\nHost: bolt\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serves_metrics_on_the_metrics_path() {
+        let exporter = PrometheusExporter::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        exporter.record(RequestRecord {
+            timestamp: chrono::Utc::now(),
+            request: "GET /".to_string(),
+            status: 200,
+            latency: Duration::from_millis(5),
+            bytes: 42,
+            bytes_sent: 0,
+            error: None,
+            warmup: false,
+        });
+
+        let response = get(exporter.local_addr(), "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains(&format!("content-type: {CONTENT_TYPE}\r\n")));
+        assert!(response.contains(
+            "bolt_response_bytes_total{request=\"GET /\",status=\"200\",error=\"\",phase=\"test\"} 42\n"
+        ));
+
+        let response = get(exporter.local_addr(), "/").await;
+        assert!(
+            response.starts_with("HTTP/1.1 404 Not Found\r\n"),
+            "{response}"
+        );
+    }
+
+    /// 立即返回 200 和 100 字节响应体的客户端
+    struct FixedClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for FixedClient {
+        async fn execute(&self, _request: &HttpRequest) -> Result<HttpResponse, HttpClientError> {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Ok(HttpResponse {
+                status: 200,
+                headers: Headers::new(),
+                body: None,
+                body_size: 100,
+                duration: Duration::from_millis(5),
+                timestamp: chrono::Utc::now(),
+                timing: Default::default(),
+            })
+        }
+
+        async fn close(&self) -> Result<(), HttpClientError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn byte_counters_match_the_load_test_result() {
+        let exporter = Arc::new(PrometheusExporter::bind("127.0.0.1:0".parse().unwrap()).unwrap());
+        let request = HttpRequest {
+            url: Url::parse("http://stub.test/upload").unwrap(),
+            body: Some(Body::text("hello")),
+            ..HttpRequest::default()
+        };
+        let config = LoadTestConfig {
+            concurrent_users: 2,
+            duration_secs: 1,
+            warmup_secs: 1,
+            ramp_up_secs: 0,
+            requests: vec![RequestTemplate::new("upload", request)],
+            ..LoadTestConfig::default()
+        };
+        let result = LoadTestUseCaseImpl::new(FixedClient)
+            .with_request_log(exporter.clone())
+            .execute(config, |_, _| {})
+            .await
+            .unwrap();
+        assert!(result.total_requests > 0 && result.warmup_requests > 0);
+
+        // 指标端点的 `PerformanceMetrics` 和结果来自同一份请求记录，预热请求只带标签区分
+        let mut totals = [0usize; 3];
+        for (labels, metrics) in exporter.registry().series() {
+            if !labels.warmup {
+                let snapshot = metrics.metrics.snapshot();
+                totals[0] += snapshot.total_requests;
+                totals[1] += snapshot.total_bytes_sent;
+                totals[2] += snapshot.total_bytes_received;
+            }
+        }
+        assert_eq!(
+            totals.map(|total| total as u64),
+            [
+                result.total_requests,
+                result.bytes_sent,
+                result.bytes_received
+            ]
+        );
+        assert_eq!(result.bytes_sent, result.total_requests * 5);
+        assert_eq!(result.bytes_received, result.total_requests * 100);
+    }
+}
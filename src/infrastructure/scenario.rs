@@ -0,0 +1,231 @@
+//! 场景文件读取
+
+use super::feeder::{FeederError, load_feeder};
+use crate::domain::entities::{LoadTestConfig, Scenario};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScenarioError {
+    #[error("Failed to read scenario file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unsupported scenario format: {0} (expected .yaml, .yml, .toml or .json)")]
+    UnsupportedFormat(String),
+
+    #[error("Failed to parse scenario: {0}")]
+    Parse(String),
+
+    #[error("Invalid scenario: {0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Feeder(#[from] FeederError),
+}
+
+/// 场景文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ScenarioFormat {
+    /// 按文件扩展名判断格式
+    pub fn from_path(path: &Path) -> Result<Self, ScenarioError> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            _ => Err(ScenarioError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// 读取场景文件，数据源的相对路径改为相对场景文件所在目录
+pub fn load_scenario(path: impl AsRef<Path>) -> Result<Scenario, ScenarioError> {
+    let path = path.as_ref();
+    let format = ScenarioFormat::from_path(path)?;
+    let content = std::fs::read_to_string(path)?;
+    let mut scenario = parse_scenario(&content, format)?;
+
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    for feeder in &mut scenario.feeders {
+        if feeder.path.is_relative() {
+            feeder.path = base_dir.join(&feeder.path);
+        }
+    }
+    Ok(scenario)
+}
+
+/// 解析场景内容
+pub fn parse_scenario(content: &str, format: ScenarioFormat) -> Result<Scenario, ScenarioError> {
+    match format {
+        ScenarioFormat::Yaml => {
+            serde_yaml::from_str(content).map_err(|e| ScenarioError::Parse(e.to_string()))
+        }
+        ScenarioFormat::Toml => {
+            toml::from_str(content).map_err(|e| ScenarioError::Parse(e.to_string()))
+        }
+        ScenarioFormat::Json => {
+            serde_json::from_str(content).map_err(|e| ScenarioError::Parse(e.to_string()))
+        }
+    }
+}
+
+/// 读取场景引用的数据文件并构建压测配置，配置错误在开始压测前暴露
+pub fn load_test_config(scenario: &Scenario) -> Result<LoadTestConfig, ScenarioError> {
+    let feeders = scenario
+        .feeders
+        .iter()
+        .map(|spec| load_feeder(&spec.path, spec.strategy))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let config = scenario
+        .load_test_config(feeders)
+        .map_err(ScenarioError::Invalid)?;
+    config.load_profile().map_err(ScenarioError::Invalid)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::FeedStrategy;
+
+    const YAML: &str = r#"
+name: checkout
+base_url: https://shop.test
+headers:
+  content-type: application/json
+feeders:
+  - path: data/users.csv
+    strategy: unique
+requests:
+  - name: cart
+    path: /cart/{{user}}
+    headers:
+      Content-Type: text/plain
+    checks:
+      - status: 200
+load:
+  concurrent_users: 5
+  duration_secs: 20
+thresholds:
+  - p95 < 500ms
+"#;
+
+    const TOML: &str = r#"
+name = "checkout"
+base_url = "https://shop.test"
+thresholds = ["p95 < 500ms"]
+
+[headers]
+content-type = "application/json"
+
+[[feeders]]
+path = "data/users.csv"
+strategy = "unique"
+
+[[requests]]
+name = "cart"
+path = "/cart/{{user}}"
+headers = { Content-Type = "text/plain" }
+checks = [{ status = 200 }]
+
+[load]
+concurrent_users = 5
+duration_secs = 20
+"#;
+
+    const JSON: &str = r#"{
+        "name": "checkout",
+        "base_url": "https://shop.test",
+        "headers": { "content-type": "application/json" },
+        "feeders": [{ "path": "data/users.csv", "strategy": "unique" }],
+        "requests": [{
+            "name": "cart",
+            "path": "/cart/{{user}}",
+            "headers": { "Content-Type": "text/plain" },
+            "checks": [{ "status": 200 }]
+        }],
+        "load": { "concurrent_users": 5, "duration_secs": 20 },
+        "thresholds": ["p95 < 500ms"]
+    }"#;
+
+    #[test]
+    fn parses_the_same_scenario_from_each_format() {
+        for (content, format) in [
+            (YAML, ScenarioFormat::Yaml),
+            (TOML, ScenarioFormat::Toml),
+            (JSON, ScenarioFormat::Json),
+        ] {
+            let scenario = parse_scenario(content, format).unwrap();
+            assert_eq!(scenario.display_name(), "checkout", "{format:?}");
+            assert_eq!(scenario.feeders[0].strategy, FeedStrategy::Unique);
+            assert_eq!(scenario.load.concurrent_users, 5);
+            assert_eq!(scenario.load.duration_secs, 20);
+            assert_eq!(scenario.thresholds[0].to_string(), "p95 < 500ms");
+
+            let templates = scenario.request_templates().unwrap();
+            let request = &templates[0].request;
+            assert_eq!(request.url.as_str(), "https://shop.test/cart/{{user}}");
+            assert_eq!(request.headers.len(), 1, "{format:?}");
+            assert_eq!(
+                request.headers.get_ignore_case("content-type").unwrap(),
+                "text/plain"
+            );
+            assert_eq!(templates[0].checks.len(), 1);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_formats() {
+        let error = parse_scenario(
+            "base_url: https://shop.test\nrequests: []\nusers: 5\n",
+            ScenarioFormat::Yaml,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&error, ScenarioError::Parse(message) if message.contains("users")),
+            "{error}"
+        );
+        assert!(matches!(
+            ScenarioFormat::from_path(Path::new("scenario.ini")),
+            Err(ScenarioError::UnsupportedFormat(_))
+        ));
+        assert_eq!(
+            ScenarioFormat::from_path(Path::new("a/scenario.YML")).unwrap(),
+            ScenarioFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn loads_feeders_relative_to_the_scenario_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+        std::fs::write(dir.path().join("data/users.csv"), "user\nalice\nbob\n").unwrap();
+        let path = dir.path().join("checkout.yaml");
+        std::fs::write(&path, YAML).unwrap();
+
+        let scenario = load_scenario(&path).unwrap();
+        assert_eq!(scenario.feeders[0].path, dir.path().join("data/users.csv"));
+
+        let config = load_test_config(&scenario).unwrap();
+        assert_eq!(config.feeders[0].len(), 2);
+        assert_eq!(config.feeders[0].columns(), ["user"]);
+        assert_eq!(config.concurrent_users, 5);
+
+        std::fs::remove_file(dir.path().join("data/users.csv")).unwrap();
+        assert!(matches!(
+            load_test_config(&scenario),
+            Err(ScenarioError::Feeder(_))
+        ));
+    }
+}
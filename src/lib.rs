@@ -5,6 +5,7 @@
 pub mod application;
 pub mod domain;
 pub mod infrastructure;
+pub mod optimization;
 pub mod presentation;
 
-pub use presentation::CliHandler;
+pub use presentation::{CliHandler, LoadTestOutcome};
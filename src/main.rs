@@ -1,4 +1,24 @@
+use bolt::LoadTestOutcome;
+use bolt::domain::services::RequestOptions;
+use bolt::domain::{
+    DEFAULT_GRACE_PERIOD_SECS, DEFAULT_SIGNIFICANT_FIGURES, ExpectedStatus, FeedStrategy,
+    LoadStage, LoadTestConfig, MAX_SIGNIFICANT_FIGURES, StageTarget, Threshold, Tolerance,
+    parse_rate,
+};
+use bolt::infrastructure::export::OutputTarget;
+use bolt::infrastructure::feeder::load_feeder;
+use bolt::infrastructure::http::ClientBackend;
+use bolt::infrastructure::scenario::{load_scenario, load_test_config};
+use bolt::optimization::{HttpVersion, PerformanceConfig};
 use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// 压测完成但阈值未通过（或被阈值中止）、或对比发现退化时的退出码，与运行出错的退出码 1 区分
+const THRESHOLDS_FAILED_EXIT_CODE: i32 = 99;
+
+/// 压测被 Ctrl+C 或 SIGTERM 中断时的退出码，与 shell 中被 SIGINT 终止的进程一致
+const INTERRUPTED_EXIT_CODE: i32 = 130;
 
 #[derive(Parser)]
 #[command(name = "bolt")]
@@ -18,6 +38,9 @@ enum Commands {
         url: String,
         #[arg(short, long, default_value = "GET")]
         method: String,
+        /// HTTP 客户端实现：reqwest 或 hyper（hyper 可单独测量 TLS 握手）
+        #[arg(long, default_value = "reqwest")]
+        client: ClientBackend,
     },
     #[command(name = "load-test")]
     LoadTest {
@@ -27,6 +50,106 @@ enum Commands {
         concurrent: usize,
         #[arg(short, long, default_value = "30")]
         duration: u64,
+        /// 预热时长（秒），期间的请求不计入统计
+        #[arg(long, default_value = "0")]
+        warmup: u64,
+        /// 爬坡时长（秒），在此期间逐步启动用户或提升速率
+        #[arg(long, default_value = "0")]
+        ramp_up: u64,
+        /// 负载阶段，格式为 时长秒数:目标，目标为用户数（如 60:100）或速率（如 60:500/s），可重复
+        #[arg(long = "stage", value_parser = parse_stage)]
+        stages: Vec<LoadStage>,
+        /// 开环模式的目标到达速率，例如 500/s 或 6000/m
+        #[arg(long, value_parser = parse_rate)]
+        rate: Option<f64>,
+        /// 开环模式下允许的最大在途请求数
+        #[arg(long, default_value = "1000")]
+        max_in_flight: usize,
+        /// 闭环模式下补齐因等待响应而遗漏的样本（协调遗漏校正）
+        #[arg(long)]
+        co_backfill: bool,
+        /// 延迟直方图的有效数字位数（1-4），越高分位越精确、内存占用越大
+        #[arg(
+            long,
+            default_value_t = DEFAULT_SIGNIFICANT_FIGURES,
+            value_parser = clap::value_parser!(u8).range(1..=MAX_SIGNIFICANT_FIGURES as i64)
+        )]
+        latency_precision: u8,
+        /// 每个请求都新建连接，用于测量 TCP/TLS 握手开销
+        #[arg(long)]
+        new_connection_per_request: bool,
+        /// 数据文件（CSV / JSON Lines / JSON），各列可在 URL 中以 {{列名}} 引用（值会做百分号编码，{{列名 | raw}} 原样插入），可重复
+        #[arg(long = "feeder")]
+        feeders: Vec<PathBuf>,
+        /// 数据源取行策略：sequential、random、circular 或 unique
+        #[arg(long, default_value = "circular")]
+        feed_strategy: FeedStrategy,
+        /// 阈值，例如 "p99 < 300ms"、"error_rate < 1%"、"qps > 500"，可重复；未通过时退出码为 99
+        #[arg(long = "threshold")]
+        thresholds: Vec<Threshold>,
+        /// 计为成功的状态码，例如 200、302 或 3xx，可重复；默认 2xx 和 3xx 计为成功
+        #[arg(long = "expect-status")]
+        expected_statuses: Vec<ExpectedStatus>,
+        /// 压测过程中阈值已无法满足时提前结束压测
+        #[arg(long)]
+        abort_on_fail: bool,
+        /// 中断（Ctrl+C / SIGTERM）或中止后等待在途请求完成的最长秒数
+        #[arg(long, default_value_t = DEFAULT_GRACE_PERIOD_SECS)]
+        grace_period: u64,
+        /// 导出结果，格式为 格式=路径：json（完整结果）、csv（汇总表）、ndjson（逐请求记录）、html（报告）、junit（阈值和检查）、timeseries-csv 或 timeseries-ndjson（每秒时间序列），可重复
+        #[arg(long = "out")]
+        outputs: Vec<OutputTarget>,
+        /// 在该地址上提供压测过程中的 Prometheus 指标，例如 127.0.0.1:9464
+        #[arg(long)]
+        metrics_addr: Option<SocketAddr>,
+        /// 不使用全屏仪表盘，只输出单行进度
+        #[arg(long)]
+        no_dashboard: bool,
+        /// 压测客户端实现：reqwest 或 hyper
+        #[arg(long, default_value = "reqwest")]
+        client: ClientBackend,
+        /// hyper 客户端的 HTTP 协议版本：1.1 或 2
+        #[arg(long, default_value = "1.1")]
+        http_version: HttpVersion,
+        /// hyper 客户端每个主机保留的最大空闲连接数
+        #[arg(long, default_value = "1000")]
+        pool_max_idle: usize,
+        /// hyper 客户端空闲连接的保留时间（秒）
+        #[arg(long, default_value = "90")]
+        pool_idle_timeout: u64,
+    },
+    /// 运行场景文件（YAML / TOML / JSON）描述的压测
+    #[command(name = "run")]
+    Run {
+        /// 场景文件路径
+        scenario: PathBuf,
+        /// 导出结果，格式为 格式=路径：json（完整结果）、csv（汇总表）、ndjson（逐请求记录）、html（报告）、junit（阈值和检查）、timeseries-csv 或 timeseries-ndjson（每秒时间序列），可重复
+        #[arg(long = "out")]
+        outputs: Vec<OutputTarget>,
+        /// 在该地址上提供压测过程中的 Prometheus 指标，例如 127.0.0.1:9464
+        #[arg(long)]
+        metrics_addr: Option<SocketAddr>,
+        /// 不使用全屏仪表盘，只输出单行进度
+        #[arg(long)]
+        no_dashboard: bool,
+        /// 压测客户端实现：reqwest 或 hyper；场景中有请求提取 cookie 时 reqwest 也不跟随重定向
+        #[arg(long, default_value = "reqwest")]
+        client: ClientBackend,
+        /// hyper 客户端的 HTTP 协议版本：1.1 或 2
+        #[arg(long, default_value = "1.1")]
+        http_version: HttpVersion,
+    },
+    /// 对比两次导出的 JSON 结果，有指标退化时退出码为 99
+    #[command(name = "compare")]
+    Compare {
+        /// 基线结果（--out json=... 导出的文件）
+        baseline: PathBuf,
+        /// 本次结果
+        current: PathBuf,
+        /// 容差，例如 "p99=10%"（相对基线）、"error_rate=0.5%"（百分点），可重复；
+        /// 默认延迟和吞吐 10%、错误率 1 个百分点
+        #[arg(long = "tolerance")]
+        tolerances: Vec<Tolerance>,
     },
     #[command(name = "version")]
     Version,
@@ -39,19 +162,121 @@ async fn main() -> Result<(), anyhow::Error> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Debug { url, method } => {
-            let handler = bolt::CliHandler::new()?;
+        Commands::Debug {
+            url,
+            method,
+            client,
+        } => {
+            let handler = bolt::CliHandler::with_backend(
+                client,
+                &RequestOptions::default(),
+                &PerformanceConfig::default(),
+            )?;
             handler.handle_debug(&url, &method).await?;
         }
         Commands::LoadTest {
             url,
             concurrent,
             duration,
+            warmup,
+            ramp_up,
+            stages,
+            rate,
+            max_in_flight,
+            co_backfill,
+            latency_precision,
+            new_connection_per_request,
+            feeders,
+            feed_strategy,
+            thresholds,
+            expected_statuses,
+            abort_on_fail,
+            grace_period,
+            outputs,
+            metrics_addr,
+            no_dashboard,
+            client,
+            http_version,
+            pool_max_idle,
+            pool_idle_timeout,
+        } => {
+            let options = RequestOptions {
+                reuse_connections: !new_connection_per_request,
+                ..RequestOptions::default()
+            };
+            let performance = PerformanceConfig {
+                max_idle_per_host: pool_max_idle,
+                pool_idle_timeout_secs: pool_idle_timeout,
+                http_version,
+                ..PerformanceConfig::default()
+            };
+            let handler = bolt::CliHandler::with_backend(client, &options, &performance)?
+                .with_outputs(outputs)?
+                .with_metrics_addr(metrics_addr)?
+                .with_dashboard(!no_dashboard);
+            let feeders = feeders
+                .iter()
+                .map(|path| load_feeder(path, feed_strategy))
+                .collect::<Result<Vec<_>, _>>()?;
+            let config = LoadTestConfig {
+                url,
+                method: "GET".to_string(),
+                concurrent_users: concurrent,
+                duration_secs: duration,
+                warmup_secs: warmup,
+                ramp_up_secs: ramp_up,
+                stages,
+                arrival_rate: rate,
+                max_in_flight,
+                coordinated_omission_backfill: co_backfill,
+                latency_precision,
+                expected_statuses,
+                feeders,
+                thresholds: thresholds
+                    .into_iter()
+                    .map(|threshold| Threshold {
+                        abort_on_fail,
+                        ..threshold
+                    })
+                    .collect(),
+                grace_period_secs: grace_period,
+                ..LoadTestConfig::default()
+            };
+            exit_with(handler.handle_load_test(config).await?);
+        }
+        Commands::Run {
+            scenario,
+            outputs,
+            metrics_addr,
+            no_dashboard,
+            client,
+            http_version,
         } => {
-            let handler = bolt::CliHandler::new()?;
-            handler
-                .handle_load_test(&url, "GET", concurrent, duration)
-                .await?;
+            let scenario = load_scenario(&scenario)?;
+            let config = load_test_config(&scenario)?;
+            let performance = PerformanceConfig {
+                http_version,
+                ..PerformanceConfig::default()
+            };
+            // 跟随重定向时看不到中间响应的 Set-Cookie，提取 cookie 的场景不跟随重定向
+            let options = RequestOptions {
+                follow_redirects: !config.extracts_cookies(),
+                ..RequestOptions::default()
+            };
+            let handler = bolt::CliHandler::with_backend(client, &options, &performance)?
+                .with_outputs(outputs)?
+                .with_metrics_addr(metrics_addr)?
+                .with_dashboard(!no_dashboard);
+            exit_with(handler.handle_scenario(&scenario, config).await?);
+        }
+        Commands::Compare {
+            baseline,
+            current,
+            tolerances,
+        } => {
+            if !bolt::CliHandler::handle_compare(&baseline, &current, &tolerances)? {
+                std::process::exit(THRESHOLDS_FAILED_EXIT_CODE);
+            }
         }
         Commands::Version => {
             println!("Bolt v{}", env!("CARGO_PKG_VERSION"));
@@ -60,3 +285,24 @@ async fn main() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// 按压测结论退出，通过时正常返回
+fn exit_with(outcome: LoadTestOutcome) {
+    match outcome {
+        LoadTestOutcome::Passed => {}
+        LoadTestOutcome::Failed => std::process::exit(THRESHOLDS_FAILED_EXIT_CODE),
+        LoadTestOutcome::Interrupted => std::process::exit(INTERRUPTED_EXIT_CODE),
+    }
+}
+
+/// 解析负载阶段：`时长秒数:用户数` 或 `时长秒数:速率`
+fn parse_stage(s: &str) -> Result<LoadStage, String> {
+    let (duration, target) = s
+        .split_once(':')
+        .ok_or_else(|| format!("阶段格式应为 时长:目标，实际为: {}", s))?;
+    let duration: u64 = duration
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的阶段时长: {}", duration))?;
+    Ok(LoadStage::new(duration, target.parse::<StageTarget>()?))
+}
@@ -52,6 +52,7 @@ pub struct ConnectionPoolStats {
     pub connection_failures: usize,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for ConnectionPoolStats {
     fn default() -> Self {
         Self {
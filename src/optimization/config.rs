@@ -2,6 +2,40 @@
 //!
 //! 定义性能优化相关的配置参数
 
+use std::fmt;
+use std::str::FromStr;
+
+/// 压测客户端使用的 HTTP 协议版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// HTTP/1.1，每个连接同时只有一个请求
+    #[default]
+    Http1,
+    /// HTTP/2，单连接多路复用（明文需服务端支持 prior knowledge）
+    Http2,
+}
+
+impl FromStr for HttpVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "1" | "1.1" | "http1" | "h1" => Ok(Self::Http1),
+            "2" | "http2" | "h2" => Ok(Self::Http2),
+            _ => Err(format!("Invalid HTTP version: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http1 => write!(f, "HTTP/1.1"),
+            Self::Http2 => write!(f, "HTTP/2"),
+        }
+    }
+}
+
 /// 性能配置
 #[derive(Debug, Clone)]
 pub struct PerformanceConfig {
@@ -15,6 +49,12 @@ pub struct PerformanceConfig {
     pub http2_max_concurrent_streams: u32,
     /// 批量统计的缓冲区大小
     pub stats_buffer_size: usize,
+    /// 空闲连接的保留时间（秒）
+    pub pool_idle_timeout_secs: u64,
+    /// 建立连接的超时时间（秒）
+    pub connect_timeout_secs: u64,
+    /// HTTP 协议版本
+    pub http_version: HttpVersion,
 }
 
 impl Default for PerformanceConfig {
@@ -25,6 +65,9 @@ impl Default for PerformanceConfig {
             tcp_nodelay: true,
             http2_max_concurrent_streams: 100,
             stats_buffer_size: 1000,
+            pool_idle_timeout_secs: 90,
+            connect_timeout_secs: 10,
+            http_version: HttpVersion::Http1,
         }
     }
 }
@@ -38,6 +81,9 @@ impl PerformanceConfig {
             tcp_nodelay: true,
             http2_max_concurrent_streams: 1000,
             stats_buffer_size: 5000,
+            pool_idle_timeout_secs: 90,
+            connect_timeout_secs: 10,
+            http_version: HttpVersion::Http1,
         }
     }
     
@@ -54,6 +100,9 @@ impl PerformanceConfig {
             tcp_nodelay: true,
             http2_max_concurrent_streams: 10,
             stats_buffer_size: 100,
+            pool_idle_timeout_secs: 90,
+            connect_timeout_secs: 10,
+            http_version: HttpVersion::Http1,
         }
     }
 }
\ No newline at end of file
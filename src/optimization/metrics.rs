@@ -6,6 +6,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 /// 性能指标
+///
+/// 压测中由 `MetricsRegistry` 按标签实时累加，供 Prometheus 端点读取。结果的请求数和
+/// 字节数需要按阶段、按请求拆分和序列化，仍以 `LoadTestResult` 为准，两者来自同一份逐请求记录。
 #[derive(Debug, Default)]
 pub struct PerformanceMetrics {
     /// 总请求数
@@ -65,6 +68,7 @@ impl PerformanceMetrics {
     }
 
     /// 计算平均响应时间
+    #[allow(clippy::manual_checked_ops)]
     pub fn average_response_time(&self) -> Duration {
         let total_requests = self.total_requests.load(Ordering::Relaxed) as u64;
         let total_time_ns = self.total_response_time_ns.load(Ordering::Relaxed) as u64;
@@ -13,7 +13,9 @@
 pub mod config;
 pub mod metrics;
 pub mod client_factory;
+pub mod registry;
 
-pub use config::PerformanceConfig;
+pub use config::{HttpVersion, PerformanceConfig};
 pub use metrics::{PerformanceMetrics, MetricsSnapshot};
-pub use client_factory::{ReqwestClientFactory, ConnectionPoolStats, ConnectionPoolManager};
\ No newline at end of file
+pub use client_factory::{ReqwestClientFactory, ConnectionPoolStats, ConnectionPoolManager};
+pub use registry::{LATENCY_BUCKETS_SECONDS, LabeledMetrics, MetricLabels, MetricsRegistry};
\ No newline at end of file
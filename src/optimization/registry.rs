@@ -0,0 +1,99 @@
+//! 带标签的指标注册表
+//!
+//! 按请求名、状态码和错误类别分别维护一组 `PerformanceMetrics` 和延迟分桶计数，
+//! 供压测过程中实时读取。
+
+use super::metrics::PerformanceMetrics;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// 延迟分桶的上界（秒），与 Prometheus 客户端的默认分桶一致
+pub const LATENCY_BUCKETS_SECONDS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// 一组指标的标签
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MetricLabels {
+    pub request: String,
+    /// 状态码，请求失败时为 0
+    pub status: u16,
+    /// 错误类别，成功请求为 `None`
+    pub error: Option<String>,
+    /// 是否为预热阶段的请求
+    pub warmup: bool,
+}
+
+/// 同一组标签下的累计指标
+#[derive(Debug, Default)]
+pub struct LabeledMetrics {
+    pub metrics: PerformanceMetrics,
+    /// 每个分桶内（不累计）的请求数，最后一个为超过最大上界的请求
+    pub latency_buckets: [AtomicUsize; LATENCY_BUCKETS_SECONDS.len() + 1],
+}
+
+impl LabeledMetrics {
+    fn record(&self, success: bool, bytes_sent: usize, bytes_received: usize, latency: Duration) {
+        self.metrics.increment_request(
+            success,
+            bytes_sent,
+            bytes_received,
+            latency.as_nanos() as u64,
+        );
+        let seconds = latency.as_secs_f64();
+        let bucket = LATENCY_BUCKETS_SECONDS.partition_point(|&upper| upper < seconds);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 各分桶上界对应的累计请求数，不含 `+Inf`
+    pub fn cumulative_buckets(&self) -> [usize; LATENCY_BUCKETS_SECONDS.len()] {
+        let mut cumulative = [0; LATENCY_BUCKETS_SECONDS.len()];
+        let mut total = 0;
+        for (slot, count) in cumulative.iter_mut().zip(&self.latency_buckets) {
+            total += count.load(Ordering::Relaxed);
+            *slot = total;
+        }
+        cumulative
+    }
+}
+
+/// 指标注册表，记录和读取可以并发进行
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    series: RwLock<BTreeMap<MetricLabels, Arc<LabeledMetrics>>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个请求，`labels.error` 为 `None` 时计为成功
+    pub fn record(
+        &self,
+        labels: MetricLabels,
+        bytes_sent: usize,
+        bytes_received: usize,
+        latency: Duration,
+    ) {
+        let success = labels.error.is_none();
+        let existing = self.series.read().get(&labels).cloned();
+        let metrics = match existing {
+            Some(metrics) => metrics,
+            None => self.series.write().entry(labels).or_default().clone(),
+        };
+        metrics.record(success, bytes_sent, bytes_received, latency);
+    }
+
+    /// 当前所有标签组及其指标，按标签排序
+    pub fn series(&self) -> Vec<(MetricLabels, Arc<LabeledMetrics>)> {
+        self.series
+            .read()
+            .iter()
+            .map(|(labels, metrics)| (labels.clone(), metrics.clone()))
+            .collect()
+    }
+}
@@ -1,23 +1,114 @@
 use crate::application::{
-    ApiDebugUseCase, ApiDebugUseCaseImpl, LoadTestUseCase, LoadTestUseCaseImpl,
+    ApiDebugUseCase, ApiDebugUseCaseImpl, LoadTestControl, LoadTestUseCase, LoadTestUseCaseImpl,
 };
-use crate::domain::entities::{LoadTestConfig, LoadTestResult, LoadTestState};
-use crate::infrastructure::http::ReqwestClient;
+use crate::domain::entities::{
+    LoadTestConfig, LoadTestProgress, LoadTestResult, LoadTestState, MetricComparison,
+    RequestTiming, Scenario, ThresholdVerdict, Tolerance, compare_results, evaluate_thresholds,
+};
+use crate::domain::services::{HttpClient, RequestOptions};
+use crate::infrastructure::export::{
+    NdjsonRequestLog, OutputFormat, OutputTarget, read_json, write_result,
+};
+use crate::infrastructure::http::ClientBackend;
+use crate::infrastructure::prometheus::PrometheusExporter;
+use crate::optimization::PerformanceConfig;
+#[cfg(feature = "cli")]
+use crate::presentation::dashboard::Dashboard;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 时序瀑布图中进度条的宽度（字符数）
+const WATERFALL_WIDTH: usize = 40;
+
+/// 一次压测的结论，决定进程的退出码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadTestOutcome {
+    /// 压测完成且阈值全部通过
+    Passed,
+    /// 阈值未通过，或压测被阈值中止
+    Failed,
+    /// 压测被信号或仪表盘手动停止，结果只包含停止前完成的请求
+    Interrupted,
+}
 
 pub struct CliHandler {
-    api_debug_use_case: ApiDebugUseCaseImpl<ReqwestClient>,
-    load_test_use_case: LoadTestUseCaseImpl<ReqwestClient>,
+    api_debug_use_case: ApiDebugUseCaseImpl<Box<dyn HttpClient>>,
+    load_test_use_case: LoadTestUseCaseImpl<Box<dyn HttpClient>>,
+    outputs: Vec<OutputTarget>,
+    /// `ndjson` 导出目标的逐请求记录，压测结束后报告丢弃的记录数
+    request_log: Option<Arc<NdjsonRequestLog>>,
+    /// 终端可用时以全屏仪表盘显示压测进度
+    dashboard: bool,
 }
 
 impl CliHandler {
     pub fn new() -> Result<Self, anyhow::Error> {
-        let client = ReqwestClient::new()?;
+        Self::with_request_options(&RequestOptions::default())
+    }
+
+    /// 使用指定的请求选项创建处理器，使用 reqwest 客户端
+    pub fn with_request_options(options: &RequestOptions) -> Result<Self, anyhow::Error> {
+        Self::with_backend(
+            ClientBackend::Reqwest,
+            options,
+            &PerformanceConfig::default(),
+        )
+    }
+
+    /// 指定客户端实现创建处理器，调试和压测使用同一种实现
+    pub fn with_backend(
+        backend: ClientBackend,
+        options: &RequestOptions,
+        performance: &PerformanceConfig,
+    ) -> Result<Self, anyhow::Error> {
         Ok(Self {
-            api_debug_use_case: ApiDebugUseCaseImpl::new(client.clone()),
-            load_test_use_case: LoadTestUseCaseImpl::new(client),
+            api_debug_use_case: ApiDebugUseCaseImpl::new(backend.create(options, performance)?),
+            load_test_use_case: LoadTestUseCaseImpl::new(backend.create(options, performance)?),
+            outputs: Vec::new(),
+            request_log: None,
+            dashboard: true,
         })
     }
 
+    /// 压测结束后把结果写到这些导出目标；`ndjson` 目标在压测过程中逐请求写入
+    pub fn with_outputs(mut self, outputs: Vec<OutputTarget>) -> Result<Self, anyhow::Error> {
+        let mut request_logs = outputs
+            .iter()
+            .filter(|output| output.format == OutputFormat::Ndjson);
+        if let Some(output) = request_logs.next() {
+            if request_logs.next().is_some() {
+                anyhow::bail!("只能指定一个 ndjson 导出目标");
+            }
+            let request_log = Arc::new(NdjsonRequestLog::create(&output.path)?);
+            self.load_test_use_case = self
+                .load_test_use_case
+                .with_request_log(request_log.clone());
+            self.request_log = Some(request_log);
+        }
+        self.outputs = outputs;
+        Ok(self)
+    }
+
+    /// 关闭全屏仪表盘时改为单行进度
+    pub fn with_dashboard(mut self, dashboard: bool) -> Self {
+        self.dashboard = dashboard;
+        self
+    }
+
+    /// 在 `addr` 上提供压测过程中的实时 Prometheus 指标
+    pub fn with_metrics_addr(
+        mut self,
+        addr: Option<std::net::SocketAddr>,
+    ) -> Result<Self, anyhow::Error> {
+        if let Some(addr) = addr {
+            let exporter = PrometheusExporter::bind(addr)?;
+            println!("📈 指标端点: http://{}/metrics", exporter.local_addr());
+            self.load_test_use_case = self.load_test_use_case.with_request_log(Arc::new(exporter));
+        }
+        Ok(self)
+    }
+
     pub async fn handle_debug(&self, url: &str, method: &str) -> Result<(), anyhow::Error> {
         println!("\n🔍 API 调试: {} {}\n", method, url);
 
@@ -25,6 +116,7 @@ impl CliHandler {
             Ok(result) => {
                 println!("✅ 状态: {}", result.response.status);
                 println!("⏱️  耗时: {:.2?}", result.response.duration);
+                print_waterfall(&result.response.timing);
                 println!("\n📋 响应头:");
                 for (key, value) in result.response.headers.iter() {
                     println!("  {}: {}", key, value);
@@ -43,41 +135,105 @@ impl CliHandler {
         }
     }
 
-    pub async fn handle_load_test(
+    /// 运行场景，`config` 为由场景构建的压测配置
+    pub async fn handle_scenario(
         &self,
-        url: &str,
-        method: &str,
-        concurrent: usize,
-        duration: u64,
-    ) -> Result<(), anyhow::Error> {
-        println!(
-            "\n🚀 负载测试: {} {} (并发: {}, 时长: {}秒)\n",
-            method, url, concurrent, duration
-        );
+        scenario: &Scenario,
+        config: LoadTestConfig,
+    ) -> Result<LoadTestOutcome, anyhow::Error> {
+        println!("\n📜 场景: {}", scenario.display_name());
+        self.handle_load_test(config).await
+    }
 
-        let config = LoadTestConfig {
-            url: url.to_string(),
-            method: method.to_string(),
-            concurrent_users: concurrent,
-            duration_secs: duration,
-            warmup_secs: 0,
-            ramp_up_secs: 0,
-        };
+    /// 运行压测
+    ///
+    /// 收到 Ctrl+C 或 SIGTERM 时停止发出新请求，在宽限期内等待在途请求，
+    /// 然后照常输出和导出已收集的结果。
+    pub async fn handle_load_test(
+        &self,
+        config: LoadTestConfig,
+    ) -> Result<LoadTestOutcome, anyhow::Error> {
+        print_config(&config);
+        let run_config = config.clone();
 
         let start_time = std::time::Instant::now();
         let start_time_for_move = start_time;
 
-        match self
+        #[cfg(feature = "cli")]
+        let dashboard = (self.dashboard && Dashboard::is_supported())
+            .then(|| Dashboard::start(&config, self.load_test_use_case.control().clone()));
+        #[cfg(feature = "cli")]
+        let board = dashboard.as_ref().map(Dashboard::handle);
+
+        // 仪表盘占用终端时由仪表盘显示停止状态，不在备用屏幕上直接输出
+        #[cfg(feature = "cli")]
+        let announce = dashboard.is_none();
+        #[cfg(not(feature = "cli"))]
+        let announce = true;
+        let stop_on_signal = tokio::spawn(stop_on_signal(
+            self.load_test_use_case.control().clone(),
+            config.grace_period_secs,
+            announce,
+        ));
+        let outcome = self
             .load_test_use_case
             .execute(config, move |state, result| {
+                #[cfg(feature = "cli")]
+                if let Some(board) = &board {
+                    board.update(state, result);
+                    return;
+                }
                 print_progress(state, result, start_time_for_move);
             })
-            .await
-        {
+            .await;
+        stop_on_signal.abort();
+
+        #[cfg(feature = "cli")]
+        if let Some(dashboard) = dashboard {
+            dashboard.finish();
+        }
+
+        match outcome {
             Ok(result) => {
-                println!("\n📊 测试完成!\n");
+                if result.interrupted {
+                    println!("\n⏹️  压测已中断，以下结果只包含中断前完成的请求\n");
+                } else {
+                    println!("\n📊 测试完成!\n");
+                }
                 print_summary(&result);
-                Ok(())
+
+                let verdicts = evaluate_thresholds(&run_config.thresholds, &result);
+                if !verdicts.is_empty() {
+                    print_verdicts(&verdicts);
+                }
+                if let Some(reason) = &result.aborted {
+                    println!("\n🛑 压测已提前结束: {}", reason);
+                }
+                if result.data_exhausted {
+                    println!("\n📭 unique 数据源已取完，压测在计划时长结束前停止");
+                }
+                if result.abandoned_requests > 0 {
+                    println!(
+                        "\n⚠️  宽限期内有 {} 个在途请求未返回，未计入结果",
+                        result.abandoned_requests
+                    );
+                }
+                if let Some(request_log) = &self.request_log
+                    && request_log.dropped() > 0
+                {
+                    println!(
+                        "\n⚠️  ndjson 写入跟不上请求速率，丢弃了 {} 条逐请求记录",
+                        request_log.dropped()
+                    );
+                }
+                self.write_outputs(&run_config, &result)?;
+                Ok(if result.interrupted {
+                    LoadTestOutcome::Interrupted
+                } else if result.aborted.is_none() && verdicts.iter().all(|v| v.passed) {
+                    LoadTestOutcome::Passed
+                } else {
+                    LoadTestOutcome::Failed
+                })
             }
             Err(e) => {
                 println!("❌ 错误: {}", e);
@@ -85,6 +241,48 @@ impl CliHandler {
             }
         }
     }
+
+    /// 对比两次导出的 JSON 结果，没有指标退化时返回 `true`
+    pub fn handle_compare(
+        baseline: &Path,
+        current: &Path,
+        tolerances: &[Tolerance],
+    ) -> Result<bool, anyhow::Error> {
+        let baseline_result = read_json(baseline)?;
+        let current_result = read_json(current)?;
+        let comparisons = compare_results(&baseline_result, &current_result, tolerances);
+
+        println!("\n📐 对比: {} → {}", baseline.display(), current.display());
+        println!(
+            "  基线 {} 个请求，本次 {} 个请求",
+            baseline_result.total_requests, current_result.total_requests
+        );
+        print_comparisons(&comparisons);
+
+        let regressions = comparisons.iter().filter(|c| c.regressed).count();
+        if regressions > 0 {
+            println!("\n❌ {} 项指标退化", regressions);
+        } else {
+            println!("\n✅ 没有指标退化");
+        }
+        Ok(regressions == 0)
+    }
+
+    fn write_outputs(
+        &self,
+        config: &LoadTestConfig,
+        result: &LoadTestResult,
+    ) -> Result<(), anyhow::Error> {
+        if self.outputs.is_empty() {
+            return Ok(());
+        }
+        println!();
+        for output in &self.outputs {
+            write_result(output, config, result)?;
+            println!("💾 已导出 {}: {}", output.format, output.path.display());
+        }
+        Ok(())
+    }
 }
 
 impl Default for CliHandler {
@@ -93,7 +291,138 @@ impl Default for CliHandler {
     }
 }
 
-fn print_progress(state: LoadTestState, result: LoadTestResult, start_time: std::time::Instant) {
+/// 收到 Ctrl+C 或 SIGTERM 后停止压测；仪表盘占用终端时 `announce` 为 `false`，不直接输出提示
+async fn stop_on_signal(control: Arc<LoadTestControl>, grace_period_secs: u64, announce: bool) {
+    shutdown_signal().await;
+    if announce {
+        println!(
+            "\n⏹️  收到中断信号，停止发出新请求，最多等待 {} 秒让在途请求完成",
+            grace_period_secs
+        );
+    }
+    control.stop();
+}
+
+/// 等待 Ctrl+C（SIGINT）或 SIGTERM；无法注册信号处理时永不返回
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    if let Ok(mut terminate) =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+    {
+        tokio::select! {
+            Ok(()) = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+        return;
+    }
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+fn print_config(config: &LoadTestConfig) {
+    let target = if config.requests.is_empty() {
+        format!("{} {}", config.method, config.url)
+    } else {
+        format!("{} 个请求", config.requests.len())
+    };
+
+    if !config.stages.is_empty() {
+        println!("\n🚀 负载测试: {} ({} 个阶段)", target, config.stages.len());
+        for (index, stage) in config.stages.iter().enumerate() {
+            println!(
+                "   {}: {} 秒 → {}",
+                stage.display_name(index),
+                stage.duration_secs,
+                stage.target
+            );
+        }
+        println!();
+    } else if let Some(rate) = config.arrival_rate {
+        println!(
+            "\n🚀 负载测试: {} (速率: {}/s, 最大在途: {}, 时长: {}秒)\n",
+            target, rate, config.max_in_flight, config.duration_secs
+        );
+    } else {
+        println!(
+            "\n🚀 负载测试: {} (并发: {}, 时长: {}秒)\n",
+            target, config.concurrent_users, config.duration_secs
+        );
+    }
+
+    for template in &config.requests {
+        let weight = template
+            .weight
+            .map(|weight| format!(" (权重 {})", weight))
+            .unwrap_or_default();
+        println!(
+            "   {}: {} {}{}",
+            template.name, template.request.method, template.request.url, weight
+        );
+    }
+    for threshold in &config.thresholds {
+        let abort = if threshold.abort_on_fail {
+            " (无法满足时中止)"
+        } else {
+            ""
+        };
+        println!("   阈值: {}{}", threshold, abort);
+    }
+    for feeder in &config.feeders {
+        println!(
+            "   数据源 {}: {} 行, 列 [{}], {:?}",
+            feeder.name(),
+            feeder.len(),
+            feeder.columns().join(", "),
+            feeder.strategy()
+        );
+    }
+    if !config.requests.is_empty() || !config.feeders.is_empty() || !config.thresholds.is_empty() {
+        println!();
+    }
+}
+
+fn print_waterfall(timing: &RequestTiming) {
+    let phases = [
+        ("DNS 解析", timing.dns),
+        ("TCP 连接", timing.connect),
+        ("TLS 握手", timing.tls),
+        ("首字节", Some(timing.ttfb)),
+        ("内容传输", Some(timing.transfer)),
+    ];
+    let total = timing.total().as_secs_f64();
+
+    println!("\n⏳ 请求时序:");
+    let mut offset = Duration::ZERO;
+    for (label, duration) in phases {
+        let Some(duration) = duration else {
+            println!("  {:<10} {:>12}", label, "-");
+            continue;
+        };
+        let scale = if total > 0.0 {
+            WATERFALL_WIDTH as f64 / total
+        } else {
+            0.0
+        };
+        let start = ((offset.as_secs_f64() * scale).round() as usize).min(WATERFALL_WIDTH - 1);
+        let end = (((offset + duration).as_secs_f64() * scale).round() as usize)
+            .clamp(start + 1, WATERFALL_WIDTH);
+        println!(
+            "  {:<10} {:>12.2?} │{}{}{}│",
+            label,
+            duration,
+            " ".repeat(start),
+            "█".repeat(end - start),
+            " ".repeat(WATERFALL_WIDTH - end),
+        );
+        offset += duration;
+    }
+    if !timing.is_new_connection() {
+        println!("  （复用已有连接）");
+    }
+}
+
+fn print_progress(state: LoadTestState, result: LoadTestProgress, start_time: std::time::Instant) {
     let _ = start_time;
     let success_rate = if result.total_requests > 0 {
         result.successful_requests as f64 / result.total_requests as f64 * 100.0
@@ -116,18 +445,300 @@ fn print_summary(result: &LoadTestResult) {
     println!("│ 总请求数:        {:>25} │", result.total_requests);
     println!("│ 成功请求:        {:>25} │", result.successful_requests);
     println!("│ 失败请求:        {:>25} │", result.failed_requests);
+    println!("│ 状态码不符:      {:>25} │", result.unexpected_statuses);
     println!("├─────────────────────────────────────────────┤");
+    println!("│ 1xx 响应:        {:>25} │", result.status_1xx);
     println!("│ 2xx 响应:        {:>25} │", result.status_2xx);
+    println!("│ 3xx 响应:        {:>25} │", result.status_3xx);
     println!("│ 4xx 响应:        {:>25} │", result.status_4xx);
     println!("│ 5xx 响应:        {:>25} │", result.status_5xx);
     println!("├─────────────────────────────────────────────┤");
     println!("│ 平均延迟:        {:>25?} │", result.avg_latency);
     println!("│ 最小延迟:        {:>25?} │", result.min_latency);
     println!("│ 最大延迟:        {:>25?} │", result.max_latency);
+    println!("│ P50 延迟:        {:>25?} │", result.p50_latency);
+    println!("│ P90 延迟:        {:>25?} │", result.p90_latency);
     println!("│ P95 延迟:        {:>25?} │", result.p95_latency);
     println!("│ P99 延迟:        {:>25?} │", result.p99_latency);
+    println!("│ P99.9 延迟:      {:>25?} │", result.p999_latency);
+    println!("│ P99.99 延迟:     {:>25?} │", result.p9999_latency);
+    println!("├─────────────────────────────────────────────┤");
+    println!("│ 校正 P50 延迟:   {:>25?} │", result.corrected_latency.p50);
+    println!("│ 校正 P90 延迟:   {:>25?} │", result.corrected_latency.p90);
+    println!("│ 校正 P99 延迟:   {:>25?} │", result.corrected_latency.p99);
+    println!("│ 校正 P99.9 延迟: {:>25?} │", result.corrected_latency.p999);
     println!("├─────────────────────────────────────────────┤");
     println!("│ QPS:             {:>25.1} │", result.qps);
-    println!("│ 总耗时:          {:>25?} │", result.total_duration);
+    println!("│ 测试时长:        {:>25.2?} │", result.elapsed);
+    println!(
+        "│ 请求体带宽:      {:>25} │",
+        format!("{}/s", format_bytes(result.sent_bytes_per_sec))
+    );
+    println!(
+        "│ 响应体带宽:      {:>25} │",
+        format!("{}/s", format_bytes(result.received_bytes_per_sec))
+    );
+    println!(
+        "│ 平均请求体:      {:>25} │",
+        format_bytes(result.avg_bytes_sent())
+    );
+    println!(
+        "│ 平均响应体:      {:>25} │",
+        format_bytes(result.avg_bytes_received())
+    );
     println!("└─────────────────────────────────────────────┘");
+
+    if !result.status_codes.is_empty() {
+        print_status_codes(result);
+    }
+
+    if !result.errors.is_empty() {
+        print_error_summary(result);
+    }
+
+    if !result.stage_results.is_empty() {
+        print_stage_summary(result);
+    }
+
+    if !result.request_results.is_empty() {
+        print_request_summary(result);
+    }
+
+    if !result.checks.is_empty() {
+        print_check_summary(result);
+    }
+
+    print_phase_summary(result);
+
+    if result.warmup_requests > 0 {
+        println!("🔥 预热请求: {} 个（未计入统计）", result.warmup_requests);
+    }
+
+    if result.extraction_failures > 0 {
+        println!(
+            "⚠️  变量提取失败: {} 次迭代因未能从响应中取到值而提前结束",
+            result.extraction_failures
+        );
+    }
+
+    if result.dropped_requests > 0 {
+        println!(
+            "⚠️  未能维持目标速率: {} 个请求因在途请求已满而未发出",
+            result.dropped_requests
+        );
+    }
+}
+
+fn print_status_codes(result: &LoadTestResult) {
+    println!("\n🔢 状态码:");
+    for (status, count) in &result.status_codes {
+        println!(
+            "  {:<6} {:>10} {:>7.2}%",
+            status,
+            count,
+            *count as f64 / result.total_requests as f64 * 100.0
+        );
+    }
+}
+
+/// 每类错误显示的消息条数
+const ERROR_SAMPLES_SHOWN: usize = 3;
+
+/// 以 1024 为进制显示字节数
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+fn print_error_summary(result: &LoadTestResult) {
+    println!("\n❗ 请求错误:");
+    let mut kinds: Vec<_> = result.errors.iter().collect();
+    kinds.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.count));
+    for (kind, stats) in kinds {
+        println!(
+            "  {:<20} {:>10} {:>7.2}%",
+            kind,
+            stats.count,
+            stats.count as f64 / result.total_requests as f64 * 100.0
+        );
+        let messages = stats.top_messages();
+        for (_, message) in messages.iter().take(ERROR_SAMPLES_SHOWN) {
+            println!("    {:>8} × {}", message.count, message.sample);
+        }
+        if messages.len() > ERROR_SAMPLES_SHOWN {
+            println!("    …另有 {} 种消息", messages.len() - ERROR_SAMPLES_SHOWN);
+        }
+    }
+}
+
+fn print_phase_summary(result: &LoadTestResult) {
+    let phases = result.phase_timings.phases();
+    if phases.iter().all(|(_, histogram)| histogram.is_empty()) {
+        return;
+    }
+
+    println!("\n⏳ 请求阶段耗时:");
+    println!(
+        "  {:<10} {:>10} {:>12} {:>12} {:>12}",
+        "阶段", "样本数", "P50", "P99", "最大"
+    );
+    for (name, histogram) in phases {
+        if histogram.is_empty() {
+            continue;
+        }
+        println!(
+            "  {:<10} {:>10} {:>12.2?} {:>12.2?} {:>12.2?}",
+            name,
+            histogram.len(),
+            histogram.value_at_percentile(50.0),
+            histogram.value_at_percentile(99.0),
+            histogram.max(),
+        );
+    }
+}
+
+fn print_stage_summary(result: &LoadTestResult) {
+    println!("\n📈 阶段结果:");
+    println!(
+        "  {:<16} {:>12} {:>10} {:>8} {:>12} {:>12}",
+        "阶段", "目标", "请求数", "成功率", "P50", "P99"
+    );
+    for stage in &result.stage_results {
+        let stage_result = &stage.result;
+        let success_rate = if stage_result.total_requests > 0 {
+            stage_result.successful_requests as f64 / stage_result.total_requests as f64 * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "  {:<16} {:>12} {:>10} {:>7.1}% {:>12?} {:>12?}",
+            stage.name,
+            stage.target.to_string(),
+            stage_result.total_requests,
+            success_rate,
+            stage_result.p50_latency,
+            stage_result.p99_latency,
+        );
+    }
+}
+
+fn print_request_summary(result: &LoadTestResult) {
+    println!("\n🔀 请求结果:");
+    println!(
+        "  {:<20} {:>12} {:>10} {:>8} {:>12} {:>12} {:>12}",
+        "请求", "占比", "请求数", "成功率", "平均", "P50", "P99"
+    );
+    for request in &result.request_results {
+        let request_result = &request.result;
+        let share = if result.total_requests > 0 {
+            request_result.total_requests as f64 / result.total_requests as f64 * 100.0
+        } else {
+            0.0
+        };
+        let share = match request.share {
+            Some(expected) => format!("{:.1}%/{:.0}%", share, expected * 100.0),
+            None => format!("{:.1}%", share),
+        };
+        let success_rate = if request_result.total_requests > 0 {
+            request_result.successful_requests as f64 / request_result.total_requests as f64 * 100.0
+        } else {
+            0.0
+        };
+        println!(
+            "  {:<20} {:>12} {:>10} {:>7.1}% {:>12.2?} {:>12?} {:>12?}",
+            request.name,
+            share,
+            request_result.total_requests,
+            success_rate,
+            request_result.avg_latency,
+            request_result.p50_latency,
+            request_result.p99_latency,
+        );
+    }
+}
+
+fn print_check_summary(result: &LoadTestResult) {
+    println!("\n✅ 响应检查:");
+    println!(
+        "  {:<2} {:<20} {:<40} {:>10} {:>10} {:>8}",
+        "", "请求", "检查", "通过", "失败", "通过率"
+    );
+    for check in &result.checks {
+        let mark = if check.failed == 0 { "✓" } else { "✗" };
+        println!(
+            "  {:<2} {:<20} {:<40} {:>10} {:>10} {:>7.1}%",
+            mark,
+            check.request,
+            check.name,
+            check.passed,
+            check.failed,
+            check.pass_rate() * 100.0,
+        );
+    }
+}
+
+fn print_comparisons(comparisons: &[MetricComparison]) {
+    println!(
+        "\n  {:<2} {:<12} {:>12} {:>12} {:>10} {:>8}  结果",
+        "", "指标", "基线", "本次", "变化", "容差"
+    );
+    for comparison in comparisons {
+        let metric = comparison.metric;
+        let format = |value: Option<f64>| {
+            value
+                .map(|value| metric.format_value(value))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let (mark, outcome) = if comparison.regressed {
+            ("✗", "退化")
+        } else if !comparison.significant {
+            ("·", "差异不显著")
+        } else {
+            ("✓", "通过")
+        };
+        let tolerance = if metric.is_ratio() {
+            format!("{:.2}pp", comparison.tolerance.value * 100.0)
+        } else {
+            format!("{:.1}%", comparison.tolerance.value * 100.0)
+        };
+        println!(
+            "  {:<2} {:<12} {:>12} {:>12} {:>10} {:>8}  {}",
+            mark,
+            metric.to_string(),
+            format(comparison.baseline),
+            format(comparison.current),
+            comparison.format_change(),
+            tolerance,
+            outcome
+        );
+    }
+}
+
+fn print_verdicts(verdicts: &[ThresholdVerdict]) {
+    println!("\n🎯 阈值:");
+    println!("  {:<2} {:<28} {:>14} {:>6}", "", "阈值", "实际", "结果");
+    for verdict in verdicts {
+        let (mark, outcome) = if verdict.passed {
+            ("✓", "通过")
+        } else {
+            ("✗", "失败")
+        };
+        let observed = verdict
+            .observed
+            .map(|value| verdict.threshold.format_value(value))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {:<2} {:<28} {:>14} {:>6}",
+            mark,
+            verdict.threshold.to_string(),
+            observed,
+            outcome
+        );
+    }
 }
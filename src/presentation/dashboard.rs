@@ -0,0 +1,505 @@
+//! 压测过程中的全屏终端仪表盘
+//!
+//! 进度回调只保存最新的结果快照，由单独的线程按固定间隔重绘并读取按键：
+//! `p` 或空格暂停/继续，`q` 或 Ctrl+C 停止压测，已收集的结果照常汇总。
+
+use crate::application::LoadTestControl;
+use crate::domain::entities::{LoadTestConfig, LoadTestProgress, LoadTestState};
+use console::{Alignment, Term, pad_str, style, truncate_str};
+use indicatif::{BinaryBytes, HumanCount};
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// 重绘间隔，同时也是等待按键的超时
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 迷你图每个点覆盖的时长
+const SPARKLINE_STEP: Duration = Duration::from_secs(1);
+
+/// 迷你图最多保留的点数
+const SPARKLINE_POINTS: usize = 120;
+
+/// 状态码分布最多显示的行数，按响应数从多到少
+const STATUS_ROWS: usize = 6;
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// 渲染线程和进度回调共享的状态
+#[derive(Default)]
+struct Shared {
+    latest: Mutex<Option<(LoadTestState, LoadTestProgress)>>,
+    finished: AtomicBool,
+}
+
+/// 全屏仪表盘，`finish` 后恢复终端
+pub struct Dashboard {
+    shared: Arc<Shared>,
+    renderer: Option<JoinHandle<()>>,
+}
+
+/// 交给进度回调的句柄
+#[derive(Clone)]
+pub struct DashboardHandle {
+    shared: Arc<Shared>,
+}
+
+impl DashboardHandle {
+    pub fn update(&self, state: LoadTestState, result: LoadTestProgress) {
+        *self.shared.latest.lock().unwrap() = Some((state, result));
+    }
+}
+
+impl Dashboard {
+    /// 标准输入和标准输出都是终端时才能使用仪表盘
+    pub fn is_supported() -> bool {
+        std::io::stdin().is_terminal() && Term::stdout().is_term()
+    }
+
+    /// 切换到备用屏幕并开始重绘
+    pub fn start(config: &LoadTestConfig, control: Arc<LoadTestControl>) -> Self {
+        let shared = Arc::new(Shared::default());
+        let total = config
+            .load_profile()
+            .map(|profile| profile.total_duration())
+            .unwrap_or_default();
+        let renderer = {
+            let shared = shared.clone();
+            std::thread::spawn(move || run(&shared, &control, total))
+        };
+        Self {
+            shared,
+            renderer: Some(renderer),
+        }
+    }
+
+    pub fn handle(&self) -> DashboardHandle {
+        DashboardHandle {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// 停止重绘并回到普通屏幕
+    pub fn finish(mut self) {
+        self.shared.finished.store(true, Ordering::Relaxed);
+        if let Some(renderer) = self.renderer.take() {
+            let _ = renderer.join();
+        }
+    }
+}
+
+/// 按秒采样的吞吐和平均延迟
+struct View {
+    total: Duration,
+    started: Instant,
+    state: LoadTestState,
+    result: LoadTestProgress,
+    stopping: bool,
+    /// 上一个点的时刻、请求数、发出的请求数和延迟之和
+    last_sample: (Instant, u64, u64, Duration),
+    rps: VecDeque<f64>,
+    latency: VecDeque<f64>,
+}
+
+impl View {
+    fn new(total: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            total,
+            started: now,
+            state: LoadTestState::NotStarted,
+            result: LoadTestProgress::default(),
+            stopping: false,
+            last_sample: (now, 0, 0, Duration::ZERO),
+            rps: VecDeque::new(),
+            latency: VecDeque::new(),
+        }
+    }
+
+    fn update(&mut self, state: LoadTestState, result: LoadTestProgress) {
+        self.state = state;
+        self.result = result;
+    }
+
+    /// 每过 `SPARKLINE_STEP` 记录一个点：这段时间内的请求速率和平均延迟
+    fn sample(&mut self) {
+        let now = Instant::now();
+        let (at, requests, sent, latency_sum) = self.last_sample;
+        let elapsed = now.duration_since(at);
+        if elapsed < SPARKLINE_STEP {
+            return;
+        }
+        let delta = self.result.total_requests.saturating_sub(requests);
+        let delta_latency = self.result.total_duration.saturating_sub(latency_sum);
+        push_point(&mut self.rps, delta as f64 / elapsed.as_secs_f64());
+        let result_sent = self.result.total_requests - self.result.unsent_requests;
+        let delta_sent = result_sent.saturating_sub(sent);
+        let mean = if delta_sent == 0 {
+            0.0
+        } else {
+            delta_latency.as_secs_f64() * 1000.0 / delta_sent as f64
+        };
+        push_point(&mut self.latency, mean);
+        self.last_sample = (
+            now,
+            self.result.total_requests,
+            result_sent,
+            self.result.total_duration,
+        );
+    }
+}
+
+fn push_point(points: &mut VecDeque<f64>, value: f64) {
+    if points.len() == SPARKLINE_POINTS {
+        points.pop_front();
+    }
+    points.push_back(value);
+}
+
+fn run(shared: &Shared, control: &LoadTestControl, total: Duration) {
+    let term = Term::stdout();
+    let keyboard = keyboard::Keyboard::enable();
+    // 切换到备用屏幕，结束后原来的终端内容保持不变
+    let _ = term.write_str("\x1b[?1049h");
+    let _ = term.hide_cursor();
+
+    let mut view = View::new(total);
+    while !shared.finished.load(Ordering::Relaxed) {
+        match keyboard.as_ref().and_then(|k| k.read_key(REFRESH_INTERVAL)) {
+            Some(b'p' | b'P' | b' ') if !view.stopping && !control.is_stopped() => {
+                if control.is_paused() {
+                    control.resume();
+                } else {
+                    control.pause();
+                }
+            }
+            Some(b'q' | b'Q' | 0x03) => {
+                view.stopping = true;
+                control.resume();
+                control.stop();
+            }
+            Some(_) => {}
+            None if keyboard.is_none() => std::thread::sleep(REFRESH_INTERVAL),
+            None => {}
+        }
+
+        if let Some((state, result)) = shared.latest.lock().unwrap().take() {
+            view.update(state, result);
+        }
+        view.sample();
+        draw(&term, &view, control);
+    }
+
+    let _ = term.show_cursor();
+    let _ = term.write_str("\x1b[?1049l");
+}
+
+fn draw(term: &Term, view: &View, control: &LoadTestControl) {
+    let (_, width) = term.size();
+    let width = width as usize;
+    let result = &view.result;
+    let mut lines = Vec::new();
+
+    let elapsed = view.started.elapsed().min(view.total);
+    // 按 q 停止，或收到中断信号由外部停止
+    let state = if view.stopping || control.is_stopped() {
+        style("● 正在停止").red().to_string()
+    } else if control.is_paused() {
+        style("● 已暂停").yellow().to_string()
+    } else {
+        style(format!("● {}", state_name(view.state)))
+            .green()
+            .to_string()
+    };
+    lines.push(format!(
+        " {}  {}    已用 {} / {}    剩余 {}",
+        style("bolt 压测").bold(),
+        state,
+        format_clock(elapsed),
+        format_clock(view.total),
+        format_clock(view.total - elapsed)
+    ));
+    let ratio = if view.total.is_zero() {
+        1.0
+    } else {
+        elapsed.as_secs_f64() / view.total.as_secs_f64()
+    };
+    let bar_width = width.saturating_sub(10).min(60);
+    let filled = (ratio * bar_width as f64).round() as usize;
+    lines.push(format!(
+        " [{}{}] {:>3.0}%",
+        style("█".repeat(filled)).cyan(),
+        "░".repeat(bar_width - filled),
+        ratio * 100.0
+    ));
+    lines.push(String::new());
+
+    let success_rate = if result.total_requests > 0 {
+        result.successful_requests as f64 / result.total_requests as f64 * 100.0
+    } else {
+        0.0
+    };
+    lines.push(format!(
+        " 活跃用户 {}    请求 {}    QPS {:.1}    成功率 {:.2}%",
+        style(control.active_users()).bold(),
+        style(HumanCount(result.total_requests)).bold(),
+        result.qps,
+        success_rate
+    ));
+    lines.push(format!(
+        " 请求体 {}/s    响应体 {}/s",
+        BinaryBytes(result.sent_bytes_per_sec as u64),
+        BinaryBytes(result.received_bytes_per_sec as u64)
+    ));
+    lines.push(String::new());
+
+    let spark_width = width.saturating_sub(24).min(SPARKLINE_POINTS);
+    lines.push(format!(
+        " {} {}  {:>8.1}/s",
+        pad_str("RPS", 6, Alignment::Left, None),
+        style(sparkline(&view.rps, spark_width)).cyan(),
+        view.rps.back().copied().unwrap_or(0.0)
+    ));
+    lines.push(format!(
+        " {} {}  {:>8.1}ms",
+        pad_str("延迟", 6, Alignment::Left, None),
+        style(sparkline(&view.latency, spark_width)).magenta(),
+        view.latency.back().copied().unwrap_or(0.0)
+    ));
+    lines.push(String::new());
+
+    lines.push(format!(" {}", style("延迟分位").bold()));
+    let percentiles = [
+        ("平均", result.avg_latency),
+        ("P50", result.p50_latency),
+        ("P90", result.p90_latency),
+        ("P95", result.p95_latency),
+        ("P99", result.p99_latency),
+        ("P99.9", result.p999_latency),
+        ("最大", result.max_latency),
+    ];
+    lines.push(
+        percentiles
+            .iter()
+            .map(|(name, _)| pad_str(name, 11, Alignment::Right, None).into_owned())
+            .collect(),
+    );
+    lines.push(
+        percentiles
+            .iter()
+            .map(|(_, value)| format!("{:>11}", format!("{:.2?}", value)))
+            .collect(),
+    );
+    lines.push(String::new());
+
+    lines.push(format!(" {}", style("状态码").bold()));
+    let mut statuses: Vec<(String, u64)> = result
+        .status_codes
+        .iter()
+        .map(|(status, count)| (status.to_string(), *count))
+        .collect();
+    statuses.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    statuses.truncate(STATUS_ROWS);
+    let responded: u64 = result.status_codes.values().sum();
+    statuses.push((
+        "无响应".to_string(),
+        result.total_requests.saturating_sub(responded),
+    ));
+    for (name, count) in statuses {
+        lines.push(distribution_line(
+            &name,
+            count,
+            result.total_requests,
+            width,
+        ));
+    }
+    lines.push(String::new());
+
+    lines.push(format!(" {}", style("错误").bold()));
+    let mut errors = vec![("失败请求".to_string(), result.failed_requests)];
+    errors.extend(
+        result
+            .errors
+            .iter()
+            .map(|(kind, count)| (format!("请求错误 {}", kind), *count)),
+    );
+    errors.push(("状态码不符".to_string(), result.unexpected_statuses));
+    errors.extend(
+        result
+            .checks
+            .iter()
+            .filter(|check| check.failed > 0)
+            .map(|check| {
+                (
+                    format!("检查 {} / {}", check.request, check.name),
+                    check.failed,
+                )
+            }),
+    );
+    errors.push(("提取失败".to_string(), result.extraction_failures));
+    errors.push(("未能按时发出".to_string(), result.dropped_requests));
+    for (name, count) in errors {
+        lines.push(format!(
+            "   {} {}",
+            pad_str(&name, 30, Alignment::Left, Some("…")),
+            HumanCount(count)
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        " {}",
+        style("[p] 暂停/继续    [q] 停止并汇总结果").dim()
+    ));
+
+    let mut frame = String::from("\x1b[H");
+    for line in lines {
+        frame.push_str(&truncate_str(&line, width, ""));
+        frame.push_str("\x1b[K\r\n");
+    }
+    frame.push_str("\x1b[J");
+    let _ = term.write_str(&frame);
+}
+
+fn distribution_line(name: &str, count: u64, total: u64, width: usize) -> String {
+    let share = if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    };
+    let bar_width = width.saturating_sub(40).min(40);
+    let filled = (share * bar_width as f64).round() as usize;
+    format!(
+        "   {} {:>12} {:>7.2}%  {}",
+        pad_str(name, 8, Alignment::Left, None),
+        HumanCount(count).to_string(),
+        share * 100.0,
+        style("▇".repeat(filled)).blue()
+    )
+}
+
+/// 把最近的 `width` 个点按最大值归一化成迷你图
+fn sparkline(points: &VecDeque<f64>, width: usize) -> String {
+    let skip = points.len().saturating_sub(width);
+    let recent: Vec<f64> = points.iter().skip(skip).copied().collect();
+    let max = recent.iter().copied().fold(0.0f64, f64::max);
+    let mut line: String = recent
+        .iter()
+        .map(|&value| {
+            if max <= 0.0 {
+                SPARK_CHARS[0]
+            } else {
+                let level = (value / max * (SPARK_CHARS.len() - 1) as f64).round() as usize;
+                SPARK_CHARS[level.min(SPARK_CHARS.len() - 1)]
+            }
+        })
+        .collect();
+    line.extend(std::iter::repeat_n(' ', width - recent.len()));
+    line
+}
+
+fn state_name(state: LoadTestState) -> &'static str {
+    match state {
+        LoadTestState::NotStarted => "准备中",
+        LoadTestState::WarmingUp => "预热中",
+        LoadTestState::RampingUp => "加压中",
+        LoadTestState::Running => "运行中",
+        LoadTestState::Paused => "已暂停",
+        LoadTestState::Completed => "已完成",
+        LoadTestState::Interrupted => "已中断",
+        LoadTestState::Failed => "失败",
+    }
+}
+
+/// `mm:ss`，超过一小时为 `h:mm:ss`
+fn format_clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(unix)]
+mod keyboard {
+    use std::time::Duration;
+
+    /// 关闭行缓冲、回显和信号键的标准输入，析构时恢复原来的终端设置
+    ///
+    /// Ctrl+C 作为普通按键读入，由仪表盘转成停止压测，避免终端停留在改过的模式下。
+    pub struct Keyboard {
+        original: libc::termios,
+    }
+
+    impl Keyboard {
+        pub fn enable() -> Option<Self> {
+            // SAFETY: termios 由 tcgetattr 填充，只在标准输入上调用
+            unsafe {
+                let mut termios: libc::termios = std::mem::zeroed();
+                if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                    return None;
+                }
+                let original = termios;
+                termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+                termios.c_cc[libc::VMIN] = 0;
+                termios.c_cc[libc::VTIME] = 0;
+                if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) != 0 {
+                    return None;
+                }
+                Some(Self { original })
+            }
+        }
+
+        /// 最多等待 `timeout` 读取一个字节
+        pub fn read_key(&self, timeout: Duration) -> Option<u8> {
+            let mut pollfd = libc::pollfd {
+                fd: libc::STDIN_FILENO,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: pollfd 和 byte 在调用期间有效
+            unsafe {
+                if libc::poll(&mut pollfd, 1, timeout.as_millis() as libc::c_int) <= 0 {
+                    return None;
+                }
+                let mut byte = 0u8;
+                let read = libc::read(
+                    libc::STDIN_FILENO,
+                    (&mut byte as *mut u8).cast::<libc::c_void>(),
+                    1,
+                );
+                (read == 1).then_some(byte)
+            }
+        }
+    }
+
+    impl Drop for Keyboard {
+        fn drop(&mut self) {
+            // SAFETY: 恢复 enable 时保存的设置
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod keyboard {
+    use std::time::Duration;
+
+    /// 其他平台上不读取按键，仪表盘只显示进度
+    pub struct Keyboard;
+
+    impl Keyboard {
+        pub fn enable() -> Option<Self> {
+            None
+        }
+
+        pub fn read_key(&self, _timeout: Duration) -> Option<u8> {
+            None
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod cli_handler;
+#[cfg(feature = "cli")]
+pub mod dashboard;
 
-pub use cli_handler::CliHandler;
+pub use cli_handler::{CliHandler, LoadTestOutcome};